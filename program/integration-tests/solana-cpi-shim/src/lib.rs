@@ -0,0 +1,82 @@
+//! Off-chain `solana-cpi` for natively run programs.
+//!
+//! The upstream crate turns CPIs and return data into no-ops off-chain, which
+//! breaks helpers such as `solana_stake_interface::tools::get_minimum_delegation`.
+//! This copy forwards them to hooks installed by the test harness, which route
+//! them to solana-program-test's syscall stubs.
+
+use solana_account_info::AccountInfo;
+use solana_instruction::Instruction;
+use solana_program_error::ProgramResult;
+use solana_pubkey::Pubkey;
+use std::sync::OnceLock;
+
+/// Maximum size that can be set using [`set_return_data`]
+pub const MAX_RETURN_DATA: usize = 1024;
+
+/// Syscall implementations used off-chain
+pub struct OffchainHooks {
+    pub invoke_signed_unchecked: fn(&Instruction, &[AccountInfo], &[&[&[u8]]]) -> ProgramResult,
+    pub set_return_data: fn(&[u8]),
+    pub get_return_data: fn() -> Option<(Pubkey, Vec<u8>)>,
+}
+
+static HOOKS: OnceLock<OffchainHooks> = OnceLock::new();
+
+/// Install the off-chain syscall hooks (first call wins)
+pub fn set_offchain_hooks(hooks: OffchainHooks) {
+    let _ = HOOKS.set(hooks);
+}
+
+pub fn invoke(instruction: &Instruction, account_infos: &[AccountInfo]) -> ProgramResult {
+    invoke_signed(instruction, account_infos, &[])
+}
+
+pub fn invoke_unchecked(instruction: &Instruction, account_infos: &[AccountInfo]) -> ProgramResult {
+    invoke_signed_unchecked(instruction, account_infos, &[])
+}
+
+pub fn invoke_signed(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    // Check that the account RefCells are consistent with the request
+    for account_meta in instruction.accounts.iter() {
+        for account_info in account_infos.iter() {
+            if account_meta.pubkey == *account_info.key {
+                if account_meta.is_writable {
+                    let _ = account_info.try_borrow_mut_lamports()?;
+                    let _ = account_info.try_borrow_mut_data()?;
+                } else {
+                    let _ = account_info.try_borrow_lamports()?;
+                    let _ = account_info.try_borrow_data()?;
+                }
+                break;
+            }
+        }
+    }
+
+    invoke_signed_unchecked(instruction, account_infos, signers_seeds)
+}
+
+pub fn invoke_signed_unchecked(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    match HOOKS.get() {
+        Some(hooks) => (hooks.invoke_signed_unchecked)(instruction, account_infos, signers_seeds),
+        None => Ok(()),
+    }
+}
+
+pub fn set_return_data(data: &[u8]) {
+    if let Some(hooks) = HOOKS.get() {
+        (hooks.set_return_data)(data);
+    }
+}
+
+pub fn get_return_data() -> Option<(Pubkey, Vec<u8>)> {
+    HOOKS.get().and_then(|hooks| (hooks.get_return_data)())
+}
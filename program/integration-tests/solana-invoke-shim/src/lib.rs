@@ -0,0 +1,6 @@
+//! anchor-lang 0.32 performs CPIs through `solana-invoke`, which only links
+//! the invoke syscall on `target_os = "solana"`. Natively run programs go
+//! through the patched `solana-cpi` instead, whose hooks reach
+//! solana-program-test's syscall stubs.
+
+pub use solana_cpi::{invoke, invoke_signed, invoke_signed_unchecked, invoke_unchecked};
@@ -0,0 +1,204 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use solana_program_test::BanksClientError;
+use solana_sdk::hash::hashv;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_system_interface::program as system_program;
+use staking_express::constants::MERKLE_NODE_PREFIX;
+use staking_express::errors::StakingError;
+use staking_express::helpers::allowlist_leaf;
+use staking_express::state::*;
+
+async fn enable_allowlist(harness: &mut Harness, pool: &Pubkey) {
+    let accounts = harness.pool_config_accounts(pool);
+    let data = staking_express::instruction::UpdateAllowlistMode { enabled: true };
+    harness.send(&[ix(accounts, data)], &[]).await.unwrap();
+}
+
+async fn add_allowlist_entry(harness: &mut Harness, pool: &Pubkey, user: &Pubkey) {
+    let accounts = staking_express::accounts::AddAllowlistEntry {
+        authority: harness.authority(),
+        global_config: global_config_pda(),
+        staking_pool: *pool,
+        allowlist_entry: allowlist_pda(pool, user),
+        system_program: system_program::ID,
+    };
+    let data = staking_express::instruction::AddAllowlistEntry { user: *user };
+    harness.send(&[ix(accounts, data)], &[]).await.unwrap();
+}
+
+async fn claim_allowlist_entry(
+    harness: &mut Harness,
+    pool: &Pubkey,
+    user: &Keypair,
+    proof: Vec<[u8; 32]>,
+) -> Result<(), BanksClientError> {
+    let accounts = staking_express::accounts::ClaimAllowlistEntry {
+        user: user.pubkey(),
+        global_config: global_config_pda(),
+        staking_pool: *pool,
+        allowlist_entry: allowlist_pda(pool, &user.pubkey()),
+        system_program: system_program::ID,
+    };
+    let data = staking_express::instruction::ClaimAllowlistEntry { proof };
+    harness.send(&[ix(accounts, data)], &[user]).await
+}
+
+/// Stake into a SOL pool, passing the position owner's allowlist entry if any
+async fn gated_stake(
+    harness: &mut Harness,
+    pool: &Pubkey,
+    user: &Keypair,
+    allowlist_entry: Option<Pubkey>,
+) -> Result<(), BanksClientError> {
+    let mut accounts = harness.stake_accounts(pool, &user.pubkey());
+    accounts.allowlist_entry = allowlist_entry;
+    let data = staking_express::instruction::Stake {
+        gross_amount: LAMPORTS_PER_SOL,
+        lockup_tier: LockupTier::None,
+    };
+    harness.send(&[ix(accounts, data)], &[user]).await
+}
+
+fn merkle_node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[MERKLE_NODE_PREFIX], left.as_ref(), right.as_ref()]).to_bytes()
+}
+
+#[tokio::test]
+async fn allowlist_mode_gates_stake_on_an_entry() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let early = harness.new_user(10 * LAMPORTS_PER_SOL).await;
+    let partner = harness.new_user(10 * LAMPORTS_PER_SOL).await;
+
+    // Ungated pools accept anyone
+    gated_stake(&mut harness, &pool, &early, None)
+        .await
+        .unwrap();
+
+    enable_allowlist(&mut harness, &pool).await;
+    let result = gated_stake(&mut harness, &pool, &partner, None).await;
+    assert_error(result, StakingError::NotAllowlisted);
+
+    // An entry added by the authority opens the pool to that wallet only
+    add_allowlist_entry(&mut harness, &pool, &partner.pubkey()).await;
+    let entry = allowlist_pda(&pool, &partner.pubkey());
+    gated_stake(&mut harness, &pool, &partner, Some(entry))
+        .await
+        .unwrap();
+    let result = gated_stake(&mut harness, &pool, &early, None).await;
+    assert_error(result, StakingError::NotAllowlisted);
+
+    // Exits are never gated
+    harness.unstake(&pool, &early, 10_000_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn merkle_proof_claims_an_allowlist_entry() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    enable_allowlist(&mut harness, &pool).await;
+
+    let partners: Vec<Keypair> = vec![
+        harness.new_user(10 * LAMPORTS_PER_SOL).await,
+        harness.new_user(10 * LAMPORTS_PER_SOL).await,
+        harness.new_user(10 * LAMPORTS_PER_SOL).await,
+    ];
+    let leaves: Vec<[u8; 32]> = partners
+        .iter()
+        .map(|partner| allowlist_leaf(&partner.pubkey()))
+        .collect();
+    let left = merkle_node(leaves[0], leaves[1]);
+    let root = merkle_node(left, leaves[2]);
+
+    // No root set yet
+    let result = claim_allowlist_entry(&mut harness, &pool, &partners[2], vec![left]).await;
+    assert_error(result, StakingError::InvalidAllowlistProof);
+
+    let accounts = staking_express::accounts::SetAllowlistRoot {
+        authority: harness.authority(),
+        global_config: global_config_pda(),
+    };
+    let data = staking_express::instruction::SetAllowlistRoot { root };
+    harness.send(&[ix(accounts, data)], &[]).await.unwrap();
+
+    // A wallet outside the tree cannot reuse someone else's proof
+    let outsider = harness.new_user(10 * LAMPORTS_PER_SOL).await;
+    let result =
+        claim_allowlist_entry(&mut harness, &pool, &outsider, vec![leaves[1], leaves[2]]).await;
+    assert_error(result, StakingError::InvalidAllowlistProof);
+
+    claim_allowlist_entry(
+        &mut harness,
+        &pool,
+        &partners[0],
+        vec![leaves[1], leaves[2]],
+    )
+    .await
+    .unwrap();
+    claim_allowlist_entry(&mut harness, &pool, &partners[2], vec![left])
+        .await
+        .unwrap();
+    let entry: AllowlistEntry = harness
+        .account(&allowlist_pda(&pool, &partners[0].pubkey()))
+        .await;
+    assert_eq!(entry.user, partners[0].pubkey());
+    assert_eq!(entry.staking_pool, pool);
+
+    for partner in [&partners[0], &partners[2]] {
+        let entry = allowlist_pda(&pool, &partner.pubkey());
+        gated_stake(&mut harness, &pool, partner, Some(entry))
+            .await
+            .unwrap();
+    }
+
+    // Members that have not claimed are still gated
+    let result = gated_stake(&mut harness, &pool, &partners[1], None).await;
+    assert_error(result, StakingError::NotAllowlisted);
+}
+
+#[tokio::test]
+async fn stake_for_requires_the_beneficiarys_entry() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    enable_allowlist(&mut harness, &pool).await;
+
+    let funder = harness.new_user(10 * LAMPORTS_PER_SOL).await;
+    let beneficiary = harness.new_user(LAMPORTS_PER_SOL).await;
+    add_allowlist_entry(&mut harness, &pool, &funder.pubkey()).await;
+
+    let stake_for = |harness: &Harness, allowlist_entry: Option<Pubkey>| {
+        let mut accounts = harness.stake_accounts(&pool, &funder.pubkey());
+        accounts.beneficiary = Some(beneficiary.pubkey());
+        accounts.user_stake = user_stake_pda(&beneficiary.pubkey(), &pool);
+        accounts.allowlist_entry = allowlist_entry;
+        ix(
+            accounts,
+            staking_express::instruction::StakeFor {
+                gross_amount: LAMPORTS_PER_SOL,
+            },
+        )
+    };
+
+    // The funder's own entry does not cover a position owned by someone else
+    let funder_entry = allowlist_pda(&pool, &funder.pubkey());
+    let instruction = stake_for(&harness, Some(funder_entry));
+    assert!(harness.send(&[instruction], &[&funder]).await.is_err());
+    let instruction = stake_for(&harness, None);
+    let result = harness.send(&[instruction], &[&funder]).await;
+    assert_error(result, StakingError::NotAllowlisted);
+
+    add_allowlist_entry(&mut harness, &pool, &beneficiary.pubkey()).await;
+    let beneficiary_entry = allowlist_pda(&pool, &beneficiary.pubkey());
+    let instruction = stake_for(&harness, Some(beneficiary_entry));
+    harness.send(&[instruction], &[&funder]).await.unwrap();
+
+    let user_stake: UserStakeState = harness
+        .account(&user_stake_pda(&beneficiary.pubkey(), &pool))
+        .await;
+    assert_eq!(user_stake.user, beneficiary.pubkey());
+    assert!(user_stake.staked_amount > 0);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use common::*;
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_system_interface::program as system_program;
+use staking_express::constants::BONUS_INITIAL_COUNTDOWN;
+use staking_express::errors::StakingError;
+use staking_express::state::*;
+
+fn bonus_round_pda(pool: &Pubkey, round: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            seeds::BONUS_ROUND,
+            bonus_pool_pda(pool).as_ref(),
+            round.to_le_bytes().as_ref(),
+        ],
+        &staking_express::ID,
+    )
+    .0
+}
+
+async fn claim_last_ten_share(
+    harness: &mut Harness,
+    pool: &Pubkey,
+    round: u64,
+    investor: &Keypair,
+) -> Result<(), BanksClientError> {
+    let accounts = staking_express::accounts::ClaimLastTenShare {
+        investor: investor.pubkey(),
+        global_config: global_config_pda(),
+        staking_pool: *pool,
+        bonus_pool: bonus_pool_pda(pool),
+        bonus_round: bonus_round_pda(pool, round),
+        mint: None,
+        investor_token_account: None,
+        vault: None,
+        token_program: None,
+        system_program: system_program::ID,
+    };
+    let data = staking_express::instruction::ClaimLastTenShare {};
+    harness.send(&[ix(accounts, data)], &[investor]).await
+}
+
+#[tokio::test]
+async fn last_ten_investors_claim_their_recorded_shares_once() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let bonus_pool = bonus_pool_pda(&pool);
+
+    let alice = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let bob = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let carol = harness.new_user(LAMPORTS_PER_SOL).await;
+    for user in [&alice, &bob] {
+        harness
+            .stake(&pool, user, 10 * LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+
+    // Close the round once the countdown expires
+    harness.advance_time(BONUS_INITIAL_COUNTDOWN + 1).await;
+    let accounts = staking_express::accounts::DistributeBonusPool {
+        caller: harness.authority(),
+        global_config: global_config_pda(),
+        staking_pool: pool,
+        bonus_pool,
+        bonus_round: bonus_round_pda(&pool, 0),
+        system_program: system_program::ID,
+    };
+    let mut distribute = ix(
+        accounts,
+        staking_express::instruction::DistributeBonusPool {},
+    );
+    for user in [&alice, &bob] {
+        distribute.accounts.push(AccountMeta::new_readonly(
+            user_stake_pda(&user.pubkey(), &pool),
+            false,
+        ));
+    }
+    harness.send(&[distribute], &[]).await.unwrap();
+
+    // The recorded shares are still held by the bonus pool
+    let round: BonusRound = harness.account(&bonus_round_pda(&pool, 0)).await;
+    assert_eq!(round.winner_count, 2);
+    assert!(round.to_last_ten > 0);
+    let alice_share = round.winners[0].share;
+    assert_eq!(round.winners[0].investor, alice.pubkey());
+    assert_eq!(alice_share + round.winners[1].share, round.to_last_ten);
+
+    // Alice pulls her share from the bonus pool, once
+    let alice_before = harness.lamports(&alice.pubkey()).await;
+    let pool_before = harness.lamports(&bonus_pool).await;
+    claim_last_ten_share(&mut harness, &pool, 0, &alice)
+        .await
+        .unwrap();
+    assert_eq!(
+        harness.lamports(&alice.pubkey()).await,
+        alice_before + alice_share
+    );
+    assert_eq!(
+        harness.lamports(&bonus_pool).await,
+        pool_before - alice_share
+    );
+    let round: BonusRound = harness.account(&bonus_round_pda(&pool, 0)).await;
+    assert!(round.winners[0].claimed);
+    assert!(!round.winners[1].claimed);
+
+    let result = claim_last_ten_share(&mut harness, &pool, 0, &alice).await;
+    assert_error(result, StakingError::NoLastTenShareAvailable);
+
+    // Investors outside the round have nothing to claim
+    let result = claim_last_ten_share(&mut harness, &pool, 0, &carol).await;
+    assert_error(result, StakingError::NoLastTenShareAvailable);
+}
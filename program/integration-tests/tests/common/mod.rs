@@ -0,0 +1,803 @@
+//! Shared harness: runs staking_express natively inside solana-program-test
+//! and builds its instructions from the Anchor-generated account structs.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+use staking_express::errors::StakingError;
+use staking_express::state::*;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Anchor's entry ties the account slice to its `'info` lifetime; the test
+/// processor hands out a shorter borrow, so the slice is leaked for the call
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    staking_express::entry(program_id, accounts, data)
+}
+
+/// Route the program's CPIs and return data to the test validator
+fn install_cpi_hooks() {
+    solana_cpi::set_offchain_hooks(solana_cpi::OffchainHooks {
+        invoke_signed_unchecked: solana_sdk::program::invoke_signed_unchecked,
+        set_return_data: solana_sdk::program::set_return_data,
+        get_return_data: solana_sdk::program::get_return_data,
+    });
+}
+
+pub struct Harness {
+    pub ctx: ProgramTestContext,
+    pub treasury: Pubkey,
+    pub material_dart_wallet: Pubkey,
+}
+
+impl Harness {
+    /// Start a validator with the protocol initialized (pool 0 is a SOL pool)
+    pub async fn new() -> Self {
+        install_cpi_hooks();
+        let mut program_test = ProgramTest::new(
+            "staking_express",
+            staking_express::ID,
+            processor!(process_instruction),
+        );
+        program_test.prefer_bpf(false);
+
+        let treasury = Pubkey::new_unique();
+        let material_dart_wallet = Pubkey::new_unique();
+        for wallet in [treasury, material_dart_wallet] {
+            program_test.add_account(
+                wallet,
+                Account::new(LAMPORTS_PER_SOL, 0, &system_program::ID),
+            );
+        }
+
+        let ctx = program_test.start_with_context().await;
+        let mut harness = Self {
+            ctx,
+            treasury,
+            material_dart_wallet,
+        };
+
+        let pool = staking_pool_pda(0);
+        let accounts = staking_express::accounts::Initialize {
+            authority: harness.authority(),
+            global_config: global_config_pda(),
+            staking_pool: pool,
+            bonus_pool: bonus_pool_pda(&pool),
+            referral_pool: referral_pool_pda(&pool),
+            treasury,
+            material_dart_wallet,
+            system_program: system_program::ID,
+        };
+        harness
+            .send(
+                &[ix(accounts, staking_express::instruction::Initialize {})],
+                &[],
+            )
+            .await
+            .unwrap();
+
+        harness
+    }
+
+    /// Protocol authority (the test payer)
+    pub fn authority(&self) -> Pubkey {
+        self.ctx.payer.pubkey()
+    }
+
+    /// Send instructions paid by the authority; `signers` are extra signers
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = self
+            .ctx
+            .get_new_latest_blockhash()
+            .await
+            .expect("blockhash");
+        let mut all_signers: Vec<&Keypair> = vec![&self.ctx.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.ctx.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// Create and fund a system-owned wallet
+    pub async fn new_user(&mut self, lamports: u64) -> Keypair {
+        let user = Keypair::new();
+        self.fund(&user.pubkey(), lamports).await;
+        user
+    }
+
+    pub async fn fund(&mut self, wallet: &Pubkey, lamports: u64) {
+        let transfer = system_instruction::transfer(&self.authority(), wallet, lamports);
+        self.send(&[transfer], &[]).await.unwrap();
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("account exists");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn exists(&mut self, address: &Pubkey) -> bool {
+        self.ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.clock().await.unix_timestamp
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.ctx.banks_client.get_sysvar::<Clock>().await.unwrap()
+    }
+
+    /// Move the cluster clock forward
+    pub async fn advance_time(&mut self, seconds: i64) {
+        let mut clock = self.clock().await;
+        clock.unix_timestamp += seconds;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// Pause or unpause operations (authority)
+    pub async fn set_pause_flags(&mut self, flags: u8, paused: bool) {
+        let accounts = staking_express::accounts::SetPaused {
+            caller: self.authority(),
+            global_config: global_config_pda(),
+        };
+        let data = staking_express::instruction::SetPauseFlags { flags, paused };
+        self.send(&[ix(accounts, data)], &[]).await.unwrap();
+    }
+
+    /// Stake accounts for a SOL pool; optional accounts default to None
+    pub fn stake_accounts(&self, pool: &Pubkey, user: &Pubkey) -> staking_express::accounts::Stake {
+        staking_express::accounts::Stake {
+            user: *user,
+            beneficiary: None,
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            user_stake: user_stake_pda(user, pool),
+            bonus_pool: bonus_pool_pda(pool),
+            referral_pool: referral_pool_pda(pool),
+            treasury: self.treasury,
+            material_dart_wallet: self.material_dart_wallet,
+            referrer: None,
+            referrer_account: None,
+            allowlist_entry: None,
+            mint: None,
+            user_token_account: None,
+            vault: None,
+            treasury_token_account: None,
+            material_dart_token_account: None,
+            referrer_token_account: None,
+            token_program: None,
+            receipt_mint: None,
+            user_receipt_account: None,
+            receipt_token_program: None,
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Stake into a SOL pool with no lockup
+    pub async fn stake(
+        &mut self,
+        pool: &Pubkey,
+        user: &Keypair,
+        gross_amount: u64,
+    ) -> Result<(), BanksClientError> {
+        self.stake_with_tier(pool, user, gross_amount, LockupTier::None)
+            .await
+    }
+
+    pub async fn stake_with_tier(
+        &mut self,
+        pool: &Pubkey,
+        user: &Keypair,
+        gross_amount: u64,
+        lockup_tier: LockupTier,
+    ) -> Result<(), BanksClientError> {
+        let accounts = self.stake_accounts(pool, &user.pubkey());
+        let data = staking_express::instruction::Stake {
+            gross_amount,
+            lockup_tier,
+        };
+        self.send(&[ix(accounts, data)], &[user]).await
+    }
+
+    /// Unstake accounts for a SOL pool; optional accounts default to None
+    pub fn unstake_accounts(
+        &self,
+        pool: &Pubkey,
+        user: &Pubkey,
+    ) -> staking_express::accounts::Unstake {
+        staking_express::accounts::Unstake {
+            user: *user,
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            user_stake: user_stake_pda(user, pool),
+            bonus_pool: bonus_pool_pda(pool),
+            referral_pool: referral_pool_pda(pool),
+            treasury: self.treasury,
+            material_dart_wallet: self.material_dart_wallet,
+            referrer: None,
+            reward_recipient: None,
+            mint: None,
+            user_token_account: None,
+            reward_recipient_token_account: None,
+            vault: None,
+            treasury_token_account: None,
+            material_dart_token_account: None,
+            referrer_token_account: None,
+            token_program: None,
+            receipt_mint: None,
+            user_receipt_account: None,
+            receipt_token_program: None,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub async fn unstake(
+        &mut self,
+        pool: &Pubkey,
+        user: &Keypair,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let accounts = self.unstake_accounts(pool, &user.pubkey());
+        let data = staking_express::instruction::Unstake { amount };
+        self.send(&[ix(accounts, data)], &[user]).await
+    }
+
+    /// Claim accounts for a SOL pool; optional accounts default to None
+    pub fn claim_accounts(
+        &self,
+        pool: &Pubkey,
+        user: &Pubkey,
+    ) -> staking_express::accounts::ClaimRewards {
+        staking_express::accounts::ClaimRewards {
+            user: *user,
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            user_stake: user_stake_pda(user, pool),
+            reward_recipient: None,
+            mint: None,
+            user_token_account: None,
+            reward_recipient_token_account: None,
+            vault: None,
+            token_program: None,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub async fn claim_rewards(
+        &mut self,
+        pool: &Pubkey,
+        user: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let accounts = self.claim_accounts(pool, &user.pubkey());
+        let data = staking_express::instruction::ClaimRewards {};
+        self.send(&[ix(accounts, data)], &[user]).await
+    }
+
+    /// Pool config accounts (authority-only pool settings)
+    pub fn pool_config_accounts(
+        &self,
+        pool: &Pubkey,
+    ) -> staking_express::accounts::UpdatePoolConfig {
+        staking_express::accounts::UpdatePoolConfig {
+            authority: self.authority(),
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+        }
+    }
+}
+
+/// Build a staking_express instruction
+pub fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: staking_express::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Assert a transaction failed with the given program error
+pub fn assert_error(result: Result<(), BanksClientError>, expected: StakingError) {
+    let code = u32::from(expected);
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected {expected:?}"),
+        Err(BanksClientError::SimulationError {
+            err: TransactionError::InstructionError(_, InstructionError::Custom(actual)),
+            ..
+        }) => assert_eq!(actual, code, "expected {expected:?}"),
+        other => panic!("expected {expected:?}, got {other:?}"),
+    }
+}
+
+pub fn global_config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[seeds::GLOBAL_CONFIG], &staking_express::ID).0
+}
+
+pub fn staking_pool_pda(pool_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::STAKING_POOL, pool_id.to_le_bytes().as_ref()],
+        &staking_express::ID,
+    )
+    .0
+}
+
+pub fn bonus_pool_pda(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::BONUS_POOL, pool.as_ref()], &staking_express::ID).0
+}
+
+pub fn referral_pool_pda(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::REFERRAL_POOL, pool.as_ref()], &staking_express::ID).0
+}
+
+pub fn user_stake_pda(user: &Pubkey, pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::USER_STAKE, user.as_ref(), pool.as_ref()],
+        &staking_express::ID,
+    )
+    .0
+}
+
+pub fn vault_pda(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::VAULT, pool.as_ref()], &staking_express::ID).0
+}
+
+pub fn receipt_mint_pda(pool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[seeds::RECEIPT_MINT, pool.as_ref()], &staking_express::ID).0
+}
+
+pub fn referrer_account_pda(pool: &Pubkey, referrer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            seeds::REFERRER,
+            referral_pool_pda(pool).as_ref(),
+            referrer.as_ref(),
+        ],
+        &staking_express::ID,
+    )
+    .0
+}
+
+pub fn allowlist_pda(pool: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::ALLOWLIST, pool.as_ref(), user.as_ref()],
+        &staking_express::ID,
+    )
+    .0
+}
+
+// ==================== Token pools ====================
+
+use anchor_spl::associated_token::{
+    get_associated_token_address_with_program_id,
+    spl_associated_token_account::instruction::create_associated_token_account,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{ExtensionType, StateWithExtensions},
+};
+
+/// Token accounts of a token pool shared by its instructions
+pub struct TokenPool {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl Harness {
+    /// Create a mint (mint and freeze authority: the payer) with the given Token-2022 extensions,
+    /// initialized by `init_extensions` before the mint itself
+    pub async fn create_mint(
+        &mut self,
+        token_program: &Pubkey,
+        decimals: u8,
+        extensions: &[ExtensionType],
+        init_extensions: impl FnOnce(&Pubkey) -> Vec<Instruction>,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let space =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)
+                .unwrap();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+
+        let mut instructions = vec![system_instruction::create_account(
+            &self.authority(),
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            token_program,
+        )];
+        instructions.extend(init_extensions(&mint.pubkey()));
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint2(
+                token_program,
+                &mint.pubkey(),
+                &self.authority(),
+                Some(&self.authority()),
+                decimals,
+            )
+            .unwrap(),
+        );
+        self.send(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// Create `owner`'s associated token account for the mint
+    pub async fn create_token_account(
+        &mut self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Pubkey {
+        let create = create_associated_token_account(&self.authority(), owner, mint, token_program);
+        self.send(&[create], &[]).await.unwrap();
+        get_associated_token_address_with_program_id(owner, mint, token_program)
+    }
+
+    pub async fn mint_to(
+        &mut self,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        destination: &Pubkey,
+        amount: u64,
+    ) {
+        let mint_to = spl_token_2022::instruction::mint_to(
+            token_program,
+            mint,
+            destination,
+            &self.authority(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.send(&[mint_to], &[]).await.unwrap();
+    }
+
+    pub async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .expect("token account exists");
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    /// Create a token pool for `mint` as the next pool_id
+    pub async fn create_token_pool(
+        &mut self,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) -> Result<TokenPool, BanksClientError> {
+        let global_config: GlobalConfig = self.account(&global_config_pda()).await;
+        let pool = staking_pool_pda(global_config.pool_count);
+        let vault = vault_pda(&pool);
+
+        let accounts = staking_express::accounts::CreateTokenPool {
+            authority: self.authority(),
+            global_config: global_config_pda(),
+            staking_pool: pool,
+            bonus_pool: bonus_pool_pda(&pool),
+            referral_pool: referral_pool_pda(&pool),
+            mint: *mint,
+            vault,
+            token_program: *token_program,
+            system_program: system_program::ID,
+        };
+        let data = staking_express::instruction::CreateTokenPool {
+            fee_schedule: FeeSchedule::default(),
+        };
+        self.send(&[ix(accounts, data)], &[]).await?;
+
+        Ok(TokenPool {
+            pool,
+            mint: *mint,
+            vault,
+            token_program: *token_program,
+        })
+    }
+
+    /// Make a SOL pool liquid with an SPL Token receipt mint (9 decimals)
+    pub async fn create_receipt_mint(&mut self, pool: &Pubkey) -> Pubkey {
+        let receipt_mint = receipt_mint_pda(pool);
+        let accounts = staking_express::accounts::CreateReceiptMint {
+            authority: self.authority(),
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            pool_mint: None,
+            receipt_mint,
+            token_program: spl_token_2022::ID,
+            system_program: system_program::ID,
+        };
+        let data = staking_express::instruction::CreateReceiptMint { decimals: 9 };
+        self.send(&[ix(accounts, data)], &[]).await.unwrap();
+        receipt_mint
+    }
+
+    /// Fee wallets' token accounts for a token pool
+    pub async fn create_fee_token_accounts(&mut self, token_pool: &TokenPool) {
+        for wallet in [self.treasury, self.material_dart_wallet] {
+            self.create_token_account(&wallet, &token_pool.mint, &token_pool.token_program)
+                .await;
+        }
+    }
+
+    /// Stake accounts for a token pool (fee token accounts are the wallets' ATAs)
+    pub fn token_stake_accounts(
+        &self,
+        token_pool: &TokenPool,
+        user: &Pubkey,
+    ) -> staking_express::accounts::Stake {
+        let ata = |owner: &Pubkey| {
+            get_associated_token_address_with_program_id(
+                owner,
+                &token_pool.mint,
+                &token_pool.token_program,
+            )
+        };
+        let mut accounts = self.stake_accounts(&token_pool.pool, user);
+        accounts.mint = Some(token_pool.mint);
+        accounts.user_token_account = Some(ata(user));
+        accounts.vault = Some(token_pool.vault);
+        accounts.treasury_token_account = Some(ata(&self.treasury));
+        accounts.material_dart_token_account = Some(ata(&self.material_dart_wallet));
+        accounts.token_program = Some(token_pool.token_program);
+        accounts
+    }
+}
+
+// ==================== Delegation ====================
+
+use anchor_lang::solana_program::sysvar;
+use solana_sdk::account::AccountSharedData;
+use solana_stake_interface::program::ID as STAKE_PROGRAM_ID;
+use solana_vote_interface::state::{VoteInit, VoteState, VoteStateVersions};
+
+pub const STAKE_HISTORY_ID: Pubkey = sysvar::stake_history::ID;
+pub const STAKE_CONFIG_ID: Pubkey =
+    anchor_lang::pubkey!("StakeConfig11111111111111111111111111111111");
+
+/// Native stake account of a pool and its record
+pub struct Delegation {
+    pub validator_stake: Pubkey,
+    pub stake_account: Pubkey,
+}
+
+pub fn validator_stake_pda(pool: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            seeds::VALIDATOR_STAKE,
+            pool.as_ref(),
+            index.to_le_bytes().as_ref(),
+        ],
+        &staking_express::ID,
+    )
+    .0
+}
+
+pub fn stake_account_pda(pool: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            seeds::STAKE_ACCOUNT,
+            pool.as_ref(),
+            index.to_le_bytes().as_ref(),
+        ],
+        &staking_express::ID,
+    )
+    .0
+}
+
+impl Harness {
+    /// Create a validator vote account to delegate to
+    pub async fn create_vote_account(&mut self) -> Pubkey {
+        let vote_account = Pubkey::new_unique();
+        let node = Pubkey::new_unique();
+        let clock = self.clock().await;
+        let vote_state = VoteState::new(
+            &VoteInit {
+                node_pubkey: node,
+                authorized_voter: node,
+                authorized_withdrawer: node,
+                commission: 0,
+            },
+            &clock,
+        );
+
+        let mut data = vec![0; VoteState::size_of()];
+        VoteState::serialize(&VoteStateVersions::new_current(vote_state), &mut data).unwrap();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let mut account = AccountSharedData::new(
+            rent.minimum_balance(data.len()),
+            data.len(),
+            &solana_vote_interface::program::ID,
+        );
+        account.set_data_from_slice(&data);
+        self.ctx.set_account(&vote_account, &account);
+        vote_account
+    }
+
+    /// Set a pool's delegation target (authority)
+    pub async fn set_delegation_target(&mut self, pool: &Pubkey, delegation_target_bps: u16) {
+        let accounts = staking_express::accounts::UpdateDelegationConfig {
+            authority: self.authority(),
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+        };
+        let data = staking_express::instruction::UpdateDelegationConfig {
+            delegation_target_bps,
+        };
+        self.send(&[ix(accounts, data)], &[]).await.unwrap();
+    }
+
+    /// Delegate pool lamports to a vote account as the next stake account
+    pub async fn delegate_stake(
+        &mut self,
+        pool: &Pubkey,
+        vote_account: &Pubkey,
+        amount: u64,
+    ) -> Result<Delegation, BanksClientError> {
+        let staking_pool: StakingPool = self.account(pool).await;
+        let index = staking_pool.stake_account_count;
+        let delegation = Delegation {
+            validator_stake: validator_stake_pda(pool, index),
+            stake_account: stake_account_pda(pool, index),
+        };
+
+        let accounts = staking_express::accounts::DelegateStake {
+            authority: self.authority(),
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            validator_stake: delegation.validator_stake,
+            stake_account: delegation.stake_account,
+            vote_account: *vote_account,
+            stake_history: STAKE_HISTORY_ID,
+            stake_config: STAKE_CONFIG_ID,
+            stake_program: STAKE_PROGRAM_ID,
+            clock: sysvar::clock::ID,
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+        };
+        let data = staking_express::instruction::DelegateStake { amount };
+        self.send(&[ix(accounts, data)], &[]).await?;
+
+        Ok(delegation)
+    }
+
+    pub async fn harvest_stake_rewards(
+        &mut self,
+        pool: &Pubkey,
+        delegation: &Delegation,
+    ) -> Result<(), BanksClientError> {
+        let accounts = staking_express::accounts::HarvestStakeRewards {
+            staking_pool: *pool,
+            validator_stake: delegation.validator_stake,
+            stake_account: delegation.stake_account,
+        };
+        let data = staking_express::instruction::HarvestStakeRewards {};
+        self.send(&[ix(accounts, data)], &[]).await
+    }
+
+    /// Deactivate a stake account, signed by `caller` (the authority if None)
+    pub async fn deactivate_stake(
+        &mut self,
+        pool: &Pubkey,
+        delegation: &Delegation,
+        caller: Option<&Keypair>,
+    ) -> Result<(), BanksClientError> {
+        let accounts = staking_express::accounts::DeactivateStake {
+            caller: caller.map_or(self.authority(), |caller| caller.pubkey()),
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            validator_stake: delegation.validator_stake,
+            stake_account: delegation.stake_account,
+            stake_program: STAKE_PROGRAM_ID,
+            clock: sysvar::clock::ID,
+        };
+        let data = staking_express::instruction::DeactivateStake {};
+        let signers: Vec<&Keypair> = caller.into_iter().collect();
+        self.send(&[ix(accounts, data)], &signers).await
+    }
+
+    /// Withdraw a deactivated stake account, signed by `caller` (the authority if None)
+    pub async fn withdraw_stake(
+        &mut self,
+        pool: &Pubkey,
+        delegation: &Delegation,
+        caller: Option<&Keypair>,
+    ) -> Result<(), BanksClientError> {
+        let accounts = staking_express::accounts::WithdrawStake {
+            caller: caller.map_or(self.authority(), |caller| caller.pubkey()),
+            authority: self.authority(),
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            validator_stake: delegation.validator_stake,
+            stake_account: delegation.stake_account,
+            stake_history: STAKE_HISTORY_ID,
+            stake_program: STAKE_PROGRAM_ID,
+            clock: sysvar::clock::ID,
+        };
+        let data = staking_express::instruction::WithdrawStake {};
+        let signers: Vec<&Keypair> = caller.into_iter().collect();
+        self.send(&[ix(accounts, data)], &signers).await
+    }
+
+    pub async fn emergency_withdraw(
+        &mut self,
+        pool: &Pubkey,
+        user: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let accounts = staking_express::accounts::EmergencyWithdraw {
+            user: user.pubkey(),
+            global_config: global_config_pda(),
+            staking_pool: *pool,
+            user_stake: user_stake_pda(&user.pubkey(), pool),
+            mint: None,
+            user_token_account: None,
+            vault: None,
+            token_program: None,
+            receipt_mint: None,
+            user_receipt_account: None,
+            receipt_token_program: None,
+            system_program: system_program::ID,
+        };
+        let data = staking_express::instruction::EmergencyWithdraw {};
+        self.send(&[ix(accounts, data)], &[user]).await
+    }
+
+    /// Advance the clock by `epochs` epochs
+    ///
+    /// Only the clock moves (warping banks exhausts file descriptors); with no stake
+    /// history for the skipped epochs the stake program treats pending activations and
+    /// deactivations as complete.
+    pub async fn advance_epochs(&mut self, epochs: u64) {
+        let mut clock = self.clock().await;
+        clock.epoch += epochs;
+        self.ctx.set_sysvar(&clock);
+    }
+}
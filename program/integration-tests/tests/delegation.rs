@@ -0,0 +1,150 @@
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use staking_express::errors::StakingError;
+use staking_express::state::*;
+
+#[tokio::test]
+async fn delegate_harvest_deactivate_and_withdraw() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let vote_account = harness.create_vote_account().await;
+    harness.set_delegation_target(&pool, 5_000).await;
+
+    let user = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    harness
+        .stake(&pool, &user, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // Delegate 4 SOL of the ~9 SOL staked (target 50%)
+    let amount = 4 * LAMPORTS_PER_SOL;
+    let pool_lamports = harness.lamports(&pool).await;
+    let delegation = harness
+        .delegate_stake(&pool, &vote_account, amount)
+        .await
+        .unwrap();
+
+    let staking_pool: StakingPool = harness.account(&pool).await;
+    let validator_stake: ValidatorStake = harness.account(&delegation.validator_stake).await;
+    assert_eq!(staking_pool.total_delegated, amount);
+    assert_eq!(staking_pool.stake_account_count, 1);
+    assert_eq!(validator_stake.delegated, amount);
+    assert_eq!(validator_stake.vote_account, vote_account);
+    assert_eq!(harness.lamports(&pool).await, pool_lamports - amount);
+    assert_eq!(
+        harness.lamports(&delegation.stake_account).await,
+        amount + validator_stake.rent_reserve
+    );
+
+    // Epoch rewards land on the stake account; harvesting books them for stakers
+    harness.advance_epochs(2).await;
+    let rewards = LAMPORTS_PER_SOL / 10;
+    harness.fund(&delegation.stake_account, rewards).await;
+    let before: StakingPool = harness.account(&pool).await;
+    harness
+        .harvest_stake_rewards(&pool, &delegation)
+        .await
+        .unwrap();
+
+    let staking_pool: StakingPool = harness.account(&pool).await;
+    let validator_stake: ValidatorStake = harness.account(&delegation.validator_stake).await;
+    assert_eq!(staking_pool.total_delegated, amount + rewards);
+    assert_eq!(validator_stake.delegated, amount + rewards);
+    assert!(staking_pool.reward_per_share > before.reward_per_share);
+
+    // Withdrawal is rejected until the stake is deactivated
+    assert!(harness
+        .withdraw_stake(&pool, &delegation, None)
+        .await
+        .is_err());
+
+    harness
+        .deactivate_stake(&pool, &delegation, None)
+        .await
+        .unwrap();
+    let validator_stake: ValidatorStake = harness.account(&delegation.validator_stake).await;
+    assert!(validator_stake.deactivated);
+
+    // After the cooldown the principal and rewards return to the pool
+    harness.advance_epochs(2).await;
+    let pool_lamports = harness.lamports(&pool).await;
+    harness
+        .withdraw_stake(&pool, &delegation, None)
+        .await
+        .unwrap();
+
+    let staking_pool: StakingPool = harness.account(&pool).await;
+    assert_eq!(staking_pool.total_delegated, 0);
+    assert_eq!(
+        harness.lamports(&pool).await,
+        pool_lamports + amount + rewards
+    );
+    assert!(!harness.exists(&delegation.validator_stake).await);
+}
+
+#[tokio::test]
+async fn anyone_can_unwind_delegation_while_unstake_is_paused() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let vote_account = harness.create_vote_account().await;
+    harness.set_delegation_target(&pool, 9_000).await;
+
+    let user = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    harness
+        .stake(&pool, &user, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let user_stake: UserStakeState = harness
+        .account(&user_stake_pda(&user.pubkey(), &pool))
+        .await;
+
+    // Delegate most of the position, leaving too little for an emergency exit
+    let delegation = harness
+        .delegate_stake(&pool, &vote_account, 8 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // Without a pause only the authority may unwind delegations
+    let result = harness
+        .deactivate_stake(&pool, &delegation, Some(&user))
+        .await;
+    assert_error(result, StakingError::Unauthorized);
+
+    harness.set_pause_flags(pause_flags::UNSTAKE, true).await;
+    let result = harness.emergency_withdraw(&pool, &user).await;
+    assert_error(result, StakingError::InsufficientLiquidity);
+
+    // While unstake is paused the user returns the delegated SOL to the pool
+    harness
+        .deactivate_stake(&pool, &delegation, Some(&user))
+        .await
+        .unwrap();
+    harness.advance_epochs(2).await;
+    let authority = harness.authority();
+    let authority_lamports = harness.lamports(&authority).await;
+    let validator_stake: ValidatorStake = harness.account(&delegation.validator_stake).await;
+    let record_rent = harness.lamports(&delegation.validator_stake).await;
+    harness
+        .withdraw_stake(&pool, &delegation, Some(&user))
+        .await
+        .unwrap();
+
+    // The authority gets back the rent it paid for the stake account and record
+    // (it also pays the fee for both signatures, as the harness fee payer)
+    let transaction_fee = 2 * 5_000;
+    assert_eq!(
+        harness.lamports(&authority).await,
+        authority_lamports + validator_stake.rent_reserve + record_rent - transaction_fee
+    );
+    let staking_pool: StakingPool = harness.account(&pool).await;
+    assert_eq!(staking_pool.total_delegated, 0);
+
+    let user_lamports = harness.lamports(&user.pubkey()).await;
+    harness.emergency_withdraw(&pool, &user).await.unwrap();
+    assert_eq!(
+        harness.lamports(&user.pubkey()).await,
+        user_lamports + user_stake.staked_amount
+    );
+}
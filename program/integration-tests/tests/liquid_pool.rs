@@ -0,0 +1,67 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_2022::spl_token_2022;
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+use staking_express::state::*;
+
+/// Receipt accounts of a liquid SOL pool position
+async fn receipt_account(harness: &mut Harness, receipt_mint: &Pubkey, user: &Keypair) -> Pubkey {
+    harness
+        .create_token_account(&user.pubkey(), receipt_mint, &spl_token_2022::ID)
+        .await
+}
+
+#[tokio::test]
+async fn liquid_redemption_burns_shares_at_the_share_price() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let receipt_mint = harness.create_receipt_mint(&pool).await;
+
+    let alice = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let bob = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let alice_receipt = receipt_account(&mut harness, &receipt_mint, &alice).await;
+    let bob_receipt = receipt_account(&mut harness, &receipt_mint, &bob).await;
+
+    for (user, receipt) in [(&alice, alice_receipt), (&bob, bob_receipt)] {
+        let mut accounts = harness.stake_accounts(&pool, &user.pubkey());
+        accounts.receipt_mint = Some(receipt_mint);
+        accounts.user_receipt_account = Some(receipt);
+        accounts.receipt_token_program = Some(spl_token_2022::ID);
+        let data = staking_express::instruction::Stake {
+            gross_amount: 10 * LAMPORTS_PER_SOL,
+            lockup_tier: LockupTier::None,
+        };
+        harness.send(&[ix(accounts, data)], &[user]).await.unwrap();
+    }
+
+    // Bob's stakers fee raised the share price above 1:1 for Alice
+    let alice_shares = harness.token_balance(&alice_receipt).await;
+    let bob_shares = harness.token_balance(&bob_receipt).await;
+    let before: StakingPool = harness.account(&pool).await;
+    assert_eq!(before.total_shares, alice_shares + bob_shares);
+    let alice_assets =
+        (alice_shares as u128 * before.total_staked as u128 / before.total_shares as u128) as u64;
+    assert!(alice_assets > alice_shares);
+
+    // Alice redeems every share
+    let mut accounts = harness.unstake_accounts(&pool, &alice.pubkey());
+    accounts.receipt_mint = Some(receipt_mint);
+    accounts.user_receipt_account = Some(alice_receipt);
+    accounts.receipt_token_program = Some(spl_token_2022::ID);
+    let data = staking_express::instruction::Unstake {
+        amount: alice_shares,
+    };
+    harness
+        .send(&[ix(accounts, data)], &[&alice])
+        .await
+        .unwrap();
+
+    // The pool's share supply and assets follow the burn
+    let after: StakingPool = harness.account(&pool).await;
+    assert_eq!(harness.token_balance(&alice_receipt).await, 0);
+    assert_eq!(after.total_shares, bob_shares);
+    assert!(after.total_staked < before.total_staked);
+    assert!(after.total_staked >= before.total_staked - alice_assets);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::Signer;
+use staking_express::constants::LOCKUP_180_DAYS;
+use staking_express::errors::StakingError;
+use staking_express::helpers::get_pending_rewards;
+use staking_express::state::*;
+
+async fn refresh_lockup(
+    harness: &mut Harness,
+    pool: &Pubkey,
+    user: &Pubkey,
+) -> Result<(), BanksClientError> {
+    let accounts = staking_express::accounts::RefreshLockup {
+        staking_pool: *pool,
+        user_stake: user_stake_pda(user, pool),
+    };
+    let data = staking_express::instruction::RefreshLockup {};
+    harness.send(&[ix(accounts, data)], &[]).await
+}
+
+async fn pending_rewards(harness: &mut Harness, pool: &Pubkey, user: &Pubkey) -> u64 {
+    let staking_pool: StakingPool = harness.account(pool).await;
+    let user_stake: UserStakeState = harness.account(&user_stake_pda(user, pool)).await;
+    get_pending_rewards(&user_stake, &staking_pool).unwrap()
+}
+
+#[tokio::test]
+async fn expired_lockup_refresh_keeps_rewards_and_drops_the_boost() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+
+    let locked = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let unlocked = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    harness
+        .stake_with_tier(&pool, &locked, 10 * LAMPORTS_PER_SOL, LockupTier::Days180)
+        .await
+        .unwrap();
+    harness
+        .stake(&pool, &unlocked, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // Active lockups cannot be refreshed
+    let result = refresh_lockup(&mut harness, &pool, &locked.pubkey()).await;
+    assert_error(result, StakingError::LockupNotExpired);
+
+    // Past expiry the position still earns at 2x until refreshed
+    harness.advance_time(LOCKUP_180_DAYS + 1).await;
+    let user_stake: UserStakeState = harness
+        .account(&user_stake_pda(&locked.pubkey(), &pool))
+        .await;
+    assert_eq!(user_stake.weighted_shares, 2 * user_stake.staked_amount);
+    let earned = pending_rewards(&mut harness, &pool, &locked.pubkey()).await;
+    assert!(earned > 0);
+
+    // Anyone can refresh it: earned rewards are carried over, the weight drops to 1x
+    refresh_lockup(&mut harness, &pool, &locked.pubkey())
+        .await
+        .unwrap();
+    let user_stake: UserStakeState = harness
+        .account(&user_stake_pda(&locked.pubkey(), &pool))
+        .await;
+    let other_stake: UserStakeState = harness
+        .account(&user_stake_pda(&unlocked.pubkey(), &pool))
+        .await;
+    let staking_pool: StakingPool = harness.account(&pool).await;
+    assert_eq!(user_stake.lockup_tier, LockupTier::None);
+    assert_eq!(user_stake.unlock_timestamp, 0);
+    assert_eq!(user_stake.weighted_shares, user_stake.staked_amount);
+    assert_eq!(user_stake.accrued_rewards, earned);
+    assert_eq!(
+        staking_pool.total_weighted_shares,
+        user_stake.weighted_shares + other_stake.weighted_shares
+    );
+    assert_eq!(
+        pending_rewards(&mut harness, &pool, &locked.pubkey()).await,
+        earned
+    );
+
+    // New rewards split evenly between the two equal positions
+    let other_before = pending_rewards(&mut harness, &pool, &unlocked.pubkey()).await;
+    let staker = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    harness
+        .stake(&pool, &staker, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let locked_gain = pending_rewards(&mut harness, &pool, &locked.pubkey()).await - earned;
+    let other_gain = pending_rewards(&mut harness, &pool, &unlocked.pubkey()).await - other_before;
+    assert!(locked_gain > 0);
+    assert_eq!(locked_gain, other_gain);
+
+    // The claim pays the carried-over rewards too
+    let balance = harness.lamports(&locked.pubkey()).await;
+    harness.claim_rewards(&pool, &locked).await.unwrap();
+    assert_eq!(
+        harness.lamports(&locked.pubkey()).await,
+        balance + earned + locked_gain
+    );
+    let user_stake: UserStakeState = harness
+        .account(&user_stake_pda(&locked.pubkey(), &pool))
+        .await;
+    assert_eq!(user_stake.accrued_rewards, 0);
+    assert_eq!(
+        pending_rewards(&mut harness, &pool, &locked.pubkey()).await,
+        0
+    );
+}
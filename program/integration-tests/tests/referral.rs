@@ -0,0 +1,248 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_system_interface::program as system_program;
+use staking_express::errors::StakingError;
+use staking_express::state::*;
+
+/// Register a funded wallet as a referrer of the pool
+async fn register_referrer(harness: &mut Harness, pool: &Pubkey) -> Keypair {
+    let referrer = harness.new_user(LAMPORTS_PER_SOL).await;
+    let accounts = staking_express::accounts::RegisterReferrer {
+        referrer: referrer.pubkey(),
+        referral_pool: referral_pool_pda(pool),
+        referrer_account: referrer_account_pda(pool, &referrer.pubkey()),
+        system_program: system_program::ID,
+    };
+    let data = staking_express::instruction::RegisterReferrer {};
+    harness
+        .send(&[ix(accounts, data)], &[&referrer])
+        .await
+        .unwrap();
+    referrer
+}
+
+/// Stake into a SOL pool through a registered referrer
+async fn stake_referred(
+    harness: &mut Harness,
+    pool: &Pubkey,
+    user: &Keypair,
+    referrer: &Pubkey,
+    gross_amount: u64,
+) -> Result<(), BanksClientError> {
+    let mut accounts = harness.stake_accounts(pool, &user.pubkey());
+    accounts.referrer = Some(*referrer);
+    accounts.referrer_account = Some(referrer_account_pda(pool, referrer));
+    let data = staking_express::instruction::Stake {
+        gross_amount,
+        lockup_tier: LockupTier::None,
+    };
+    harness.send(&[ix(accounts, data)], &[user]).await
+}
+
+/// Unstake accounts for a token pool (fee token accounts are the wallets' ATAs)
+fn token_unstake_accounts(
+    harness: &Harness,
+    token_pool: &TokenPool,
+    user: &Pubkey,
+) -> staking_express::accounts::Unstake {
+    let stake_accounts = harness.token_stake_accounts(token_pool, user);
+    let mut accounts = harness.unstake_accounts(&token_pool.pool, user);
+    accounts.mint = stake_accounts.mint;
+    accounts.user_token_account = stake_accounts.user_token_account;
+    accounts.vault = stake_accounts.vault;
+    accounts.treasury_token_account = stake_accounts.treasury_token_account;
+    accounts.material_dart_token_account = stake_accounts.material_dart_token_account;
+    accounts.token_program = stake_accounts.token_program;
+    accounts
+}
+
+#[tokio::test]
+async fn unreferred_sol_unstake_rejects_a_supplied_referrer() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let referral_pool = referral_pool_pda(&pool);
+
+    let alice = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let alice_wallet = Keypair::new();
+    harness.fund(&alice_wallet.pubkey(), LAMPORTS_PER_SOL).await;
+    harness
+        .stake(&pool, &alice, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let amount = harness
+        .account::<UserStakeState>(&user_stake_pda(&alice.pubkey(), &pool))
+        .await
+        .staked_amount;
+
+    // Alice's position has no referrer, so she cannot route the fee to her own wallet
+    let mut accounts = harness.unstake_accounts(&pool, &alice.pubkey());
+    accounts.referrer = Some(alice_wallet.pubkey());
+    let result = harness
+        .send(
+            &[ix(
+                accounts,
+                staking_express::instruction::Unstake { amount },
+            )],
+            &[&alice],
+        )
+        .await;
+    assert_error(result, StakingError::InvalidReferrer);
+
+    // Without one the referral fee lands in the referral pool it is booked to
+    let before: ReferralPool = harness.account(&referral_pool).await;
+    let lamports_before = harness.lamports(&referral_pool).await;
+    harness.unstake(&pool, &alice, amount).await.unwrap();
+    let after: ReferralPool = harness.account(&referral_pool).await;
+    let credited = after.balance - before.balance;
+    assert!(credited > 0);
+    assert_eq!(
+        harness.lamports(&referral_pool).await,
+        lamports_before + credited
+    );
+    assert_eq!(
+        harness.lamports(&alice_wallet.pubkey()).await,
+        LAMPORTS_PER_SOL
+    );
+}
+
+#[tokio::test]
+async fn unreferred_token_unstake_rejects_a_supplied_referrer() {
+    let mut harness = Harness::new().await;
+    let token_program = anchor_spl::token::spl_token::ID;
+    let mint = harness
+        .create_mint(&token_program, 6, &[], |_| vec![])
+        .await;
+    let token_pool = harness
+        .create_token_pool(&mint, &token_program)
+        .await
+        .unwrap();
+    harness.create_fee_token_accounts(&token_pool).await;
+
+    let alice = harness.new_user(LAMPORTS_PER_SOL).await;
+    let alice_tokens = harness
+        .create_token_account(&alice.pubkey(), &mint, &token_program)
+        .await;
+    harness
+        .mint_to(&mint, &token_program, &alice_tokens, 10_000_000)
+        .await;
+    let alice_wallet = Keypair::new();
+    let alice_wallet_tokens = harness
+        .create_token_account(&alice_wallet.pubkey(), &mint, &token_program)
+        .await;
+
+    let accounts = harness.token_stake_accounts(&token_pool, &alice.pubkey());
+    let data = staking_express::instruction::Stake {
+        gross_amount: 10_000_000,
+        lockup_tier: LockupTier::None,
+    };
+    harness
+        .send(&[ix(accounts, data)], &[&alice])
+        .await
+        .unwrap();
+    let amount = harness
+        .account::<UserStakeState>(&user_stake_pda(&alice.pubkey(), &token_pool.pool))
+        .await
+        .staked_amount;
+
+    // Neither a referrer nor a referrer token account may be passed for an unreferred position
+    let with_referrer = |accounts: &mut staking_express::accounts::Unstake| {
+        accounts.referrer = Some(alice_wallet.pubkey());
+        accounts.referrer_token_account = Some(alice_wallet_tokens);
+    };
+    let with_token_account = |accounts: &mut staking_express::accounts::Unstake| {
+        accounts.referrer_token_account = Some(alice_wallet_tokens);
+    };
+    for supply in [
+        &with_referrer as &dyn Fn(&mut staking_express::accounts::Unstake),
+        &with_token_account,
+    ] {
+        let mut accounts = token_unstake_accounts(&harness, &token_pool, &alice.pubkey());
+        supply(&mut accounts);
+        let result = harness
+            .send(
+                &[ix(
+                    accounts,
+                    staking_express::instruction::Unstake { amount },
+                )],
+                &[&alice],
+            )
+            .await;
+        assert_error(result, StakingError::InvalidReferrer);
+    }
+
+    // Without one the referral share stays in the vault, booked to the referral pool
+    let before: ReferralPool = harness.account(&referral_pool_pda(&token_pool.pool)).await;
+    let accounts = token_unstake_accounts(&harness, &token_pool, &alice.pubkey());
+    harness
+        .send(
+            &[ix(
+                accounts,
+                staking_express::instruction::Unstake { amount },
+            )],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let after: ReferralPool = harness.account(&referral_pool_pda(&token_pool.pool)).await;
+    assert!(after.balance > before.balance);
+    assert_eq!(harness.token_balance(&alice_wallet_tokens).await, 0);
+    let staking_pool: StakingPool = harness.account(&token_pool.pool).await;
+    assert!(
+        harness.token_balance(&token_pool.vault).await >= staking_pool.total_staked + after.balance
+    );
+}
+
+#[tokio::test]
+async fn top_ups_only_credit_the_bound_referrer() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let bob = register_referrer(&mut harness, &pool).await;
+    let carol = register_referrer(&mut harness, &pool).await;
+
+    let alice = harness.new_user(30 * LAMPORTS_PER_SOL).await;
+    stake_referred(
+        &mut harness,
+        &pool,
+        &alice,
+        &bob.pubkey(),
+        10 * LAMPORTS_PER_SOL,
+    )
+    .await
+    .unwrap();
+
+    // Alice's position is bound to Bob: a top-up through Carol is rejected
+    let result = stake_referred(
+        &mut harness,
+        &pool,
+        &alice,
+        &carol.pubkey(),
+        10 * LAMPORTS_PER_SOL,
+    )
+    .await;
+    assert_error(result, StakingError::InvalidReferrer);
+    let carol_account: ReferrerAccount = harness
+        .account(&referrer_account_pda(&pool, &carol.pubkey()))
+        .await;
+    assert_eq!(carol_account.total_volume, 0);
+    assert_eq!(carol_account.total_earned, 0);
+
+    // Through Bob it is credited to Bob
+    stake_referred(
+        &mut harness,
+        &pool,
+        &alice,
+        &bob.pubkey(),
+        10 * LAMPORTS_PER_SOL,
+    )
+    .await
+    .unwrap();
+    let bob_account: ReferrerAccount = harness
+        .account(&referrer_account_pda(&pool, &bob.pubkey()))
+        .await;
+    assert_eq!(bob_account.total_volume, 20 * LAMPORTS_PER_SOL);
+    assert_eq!(bob_account.referral_count, 1);
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use solana_program_test::BanksClientError;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::signature::{Keypair, Signer};
+use staking_express::errors::StakingError;
+use staking_express::helpers::get_pending_rewards;
+use staking_express::state::*;
+
+async fn set_reward_recipient(
+    harness: &mut Harness,
+    pool: &Pubkey,
+    user: &Keypair,
+    reward_recipient: Pubkey,
+    account: Option<Pubkey>,
+) -> Result<(), BanksClientError> {
+    let accounts = staking_express::accounts::SetRewardRecipient {
+        user: user.pubkey(),
+        staking_pool: *pool,
+        user_stake: user_stake_pda(&user.pubkey(), pool),
+        reward_recipient: account,
+    };
+    let data = staking_express::instruction::SetRewardRecipient { reward_recipient };
+    harness.send(&[ix(accounts, data)], &[user]).await
+}
+
+async fn pending_rewards(harness: &mut Harness, pool: &Pubkey, user: &Pubkey) -> u64 {
+    let staking_pool: StakingPool = harness.account(pool).await;
+    let user_stake: UserStakeState = harness.account(&user_stake_pda(user, pool)).await;
+    get_pending_rewards(&user_stake, &staking_pool).unwrap()
+}
+
+#[tokio::test]
+async fn reward_recipient_must_be_a_funded_wallet() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let user = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    harness
+        .stake(&pool, &user, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // The recipient account must be passed
+    let wallet = harness.new_user(LAMPORTS_PER_SOL).await.pubkey();
+    let result = set_reward_recipient(&mut harness, &pool, &user, wallet, None).await;
+    assert_error(result, StakingError::InvalidRewardRecipient);
+
+    // An unfunded wallet would fail the rent check on small SOL payouts
+    let unfunded = Pubkey::new_unique();
+    let result = set_reward_recipient(&mut harness, &pool, &user, unfunded, Some(unfunded)).await;
+    assert_error(result, StakingError::RewardRecipientNotFunded);
+
+    // Program-owned accounts are rejected
+    let result = set_reward_recipient(&mut harness, &pool, &user, pool, Some(pool)).await;
+    assert_error(result, StakingError::InvalidAccountOwner);
+
+    set_reward_recipient(&mut harness, &pool, &user, wallet, Some(wallet))
+        .await
+        .unwrap();
+    let user_stake: UserStakeState = harness
+        .account(&user_stake_pda(&user.pubkey(), &pool))
+        .await;
+    assert_eq!(user_stake.reward_recipient, wallet);
+
+    // Clearing needs no account
+    set_reward_recipient(&mut harness, &pool, &user, Pubkey::default(), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn claim_and_unstake_pay_rewards_to_the_recipient() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let user = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let other = harness.new_user(40 * LAMPORTS_PER_SOL).await;
+    let recipient = harness.new_user(LAMPORTS_PER_SOL).await.pubkey();
+
+    harness
+        .stake(&pool, &user, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    set_reward_recipient(&mut harness, &pool, &user, recipient, Some(recipient))
+        .await
+        .unwrap();
+    harness
+        .stake(&pool, &other, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // Claim: the position's recipient must be passed and receives the rewards
+    let result = harness.claim_rewards(&pool, &user).await;
+    assert_error(result, StakingError::InvalidRewardRecipient);
+
+    let pending = pending_rewards(&mut harness, &pool, &user.pubkey()).await;
+    let user_lamports = harness.lamports(&user.pubkey()).await;
+    let recipient_lamports = harness.lamports(&recipient).await;
+    let mut accounts = harness.claim_accounts(&pool, &user.pubkey());
+    accounts.reward_recipient = Some(recipient);
+    let data = staking_express::instruction::ClaimRewards {};
+    harness.send(&[ix(accounts, data)], &[&user]).await.unwrap();
+    assert_eq!(
+        harness.lamports(&recipient).await,
+        recipient_lamports + pending
+    );
+    assert_eq!(harness.lamports(&user.pubkey()).await, user_lamports);
+
+    // Unstake: the reward leg goes to the recipient, the principal to the owner
+    harness
+        .stake(&pool, &other, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let pending = pending_rewards(&mut harness, &pool, &user.pubkey()).await;
+    assert!(pending > 0);
+    let user_lamports = harness.lamports(&user.pubkey()).await;
+    let recipient_lamports = harness.lamports(&recipient).await;
+    let mut accounts = harness.unstake_accounts(&pool, &user.pubkey());
+    accounts.reward_recipient = Some(recipient);
+    let amount = LAMPORTS_PER_SOL;
+    let data = staking_express::instruction::Unstake { amount };
+    harness.send(&[ix(accounts, data)], &[&user]).await.unwrap();
+    assert_eq!(
+        harness.lamports(&recipient).await,
+        recipient_lamports + pending
+    );
+    let principal = harness.lamports(&user.pubkey()).await - user_lamports;
+    assert!(principal > 0 && principal < amount);
+
+    // A recipient drained since it was set cannot take a payout below the rent
+    // minimum; the owner is paid instead of failing the claim
+    harness
+        .ctx
+        .set_account(&recipient, &AccountSharedData::default());
+    let small = harness.new_user(LAMPORTS_PER_SOL).await;
+    harness.stake(&pool, &small, 10_000_000).await.unwrap();
+    let pending = pending_rewards(&mut harness, &pool, &user.pubkey()).await;
+    assert!(pending > 0);
+    let user_lamports = harness.lamports(&user.pubkey()).await;
+    let mut accounts = harness.claim_accounts(&pool, &user.pubkey());
+    accounts.reward_recipient = Some(recipient);
+    let data = staking_express::instruction::ClaimRewards {};
+    harness.send(&[ix(accounts, data)], &[&user]).await.unwrap();
+    assert_eq!(
+        harness.lamports(&user.pubkey()).await,
+        user_lamports + pending
+    );
+    assert_eq!(harness.lamports(&recipient).await, 0);
+}
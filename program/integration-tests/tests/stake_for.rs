@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use staking_express::helpers::get_pending_rewards;
+use staking_express::state::*;
+
+#[tokio::test]
+async fn stake_for_carries_the_beneficiarys_rewards_over() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+    let beneficiary = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    let funder = harness.new_user(30 * LAMPORTS_PER_SOL).await;
+    let position = user_stake_pda(&beneficiary.pubkey(), &pool);
+
+    harness
+        .stake(&pool, &beneficiary, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    // Another staker's fee earns the beneficiary rewards
+    harness
+        .stake(&pool, &funder, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    let staking_pool: StakingPool = harness.account(&pool).await;
+    let before: UserStakeState = harness.account(&position).await;
+    let pending = get_pending_rewards(&before, &staking_pool).unwrap();
+    assert!(pending > 0);
+
+    let mut accounts = harness.stake_accounts(&pool, &funder.pubkey());
+    accounts.beneficiary = Some(beneficiary.pubkey());
+    accounts.user_stake = position;
+    let data = staking_express::instruction::StakeFor {
+        gross_amount: 10 * LAMPORTS_PER_SOL,
+    };
+    harness
+        .send(&[ix(accounts, data)], &[&funder])
+        .await
+        .unwrap();
+
+    // Only the funded net amount is added; the rewards (plus the position's share
+    // of this stake's fee) stay claimable on top
+    let staking_pool: StakingPool = harness.account(&pool).await;
+    let after: UserStakeState = harness.account(&position).await;
+    assert_eq!(after.staked_amount, 2 * before.staked_amount);
+    assert!(after.accrued_rewards > pending);
+    let pending_after = get_pending_rewards(&after, &staking_pool).unwrap();
+    assert_eq!(pending_after, after.accrued_rewards);
+
+    let balance = harness.lamports(&beneficiary.pubkey()).await;
+    harness.claim_rewards(&pool, &beneficiary).await.unwrap();
+    assert_eq!(
+        harness.lamports(&beneficiary.pubkey()).await,
+        balance + pending_after
+    );
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022::{self, extension::ExtensionType};
+use common::*;
+use solana_sdk::signature::Signer;
+use staking_express::errors::StakingError;
+use staking_express::state::*;
+
+#[tokio::test]
+async fn token_pool_minimums_scale_with_mint_decimals() {
+    let mut harness = Harness::new().await;
+    let token_program = spl_token::ID;
+
+    // USDC-like mint: 6 decimals, so 0.01 token = 10_000 base units
+    let mint = harness
+        .create_mint(&token_program, 6, &[], |_| vec![])
+        .await;
+    let token_pool = harness
+        .create_token_pool(&mint, &token_program)
+        .await
+        .unwrap();
+    harness.create_fee_token_accounts(&token_pool).await;
+
+    let staking_pool: StakingPool = harness.account(&token_pool.pool).await;
+    assert_eq!(staking_pool.decimals, 6);
+    let bonus_pool: BonusPool = harness.account(&bonus_pool_pda(&token_pool.pool)).await;
+    assert_eq!(bonus_pool.config.last_ten_threshold, 1_000_000);
+
+    let user = harness.new_user(LAMPORTS_PER_SOL).await;
+    let user_tokens = harness
+        .create_token_account(&user.pubkey(), &mint, &token_program)
+        .await;
+    harness
+        .mint_to(&mint, &token_program, &user_tokens, 10_000_000)
+        .await;
+
+    // Below 0.01 token is rejected
+    let accounts = harness.token_stake_accounts(&token_pool, &user.pubkey());
+    let result = harness
+        .send(
+            &[ix(
+                accounts,
+                staking_express::instruction::Stake {
+                    gross_amount: 9_999,
+                    lockup_tier: LockupTier::None,
+                },
+            )],
+            &[&user],
+        )
+        .await;
+    assert_error(result, StakingError::StakeTooSmall);
+
+    // 0.01 token is accepted but does not touch the bonus round
+    let accounts = harness.token_stake_accounts(&token_pool, &user.pubkey());
+    harness
+        .send(
+            &[ix(
+                accounts,
+                staking_express::instruction::Stake {
+                    gross_amount: 10_000,
+                    lockup_tier: LockupTier::None,
+                },
+            )],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    let bonus_pool: BonusPool = harness.account(&bonus_pool_pda(&token_pool.pool)).await;
+    assert_eq!(bonus_pool.investor_count, 0);
+
+    // 1 whole token qualifies for the last-10 list (1e9 base units would not be needed)
+    let accounts = harness.token_stake_accounts(&token_pool, &user.pubkey());
+    harness
+        .send(
+            &[ix(
+                accounts,
+                staking_express::instruction::Stake {
+                    gross_amount: 1_000_000,
+                    lockup_tier: LockupTier::None,
+                },
+            )],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    let bonus_pool: BonusPool = harness.account(&bonus_pool_pda(&token_pool.pool)).await;
+    assert_eq!(bonus_pool.investor_count, 1);
+
+    // Stakes stay in the vault, minus the fee legs paid out to the fee wallets
+    let user_stake: UserStakeState = harness
+        .account(&user_stake_pda(&user.pubkey(), &token_pool.pool))
+        .await;
+    assert_eq!(
+        harness.token_balance(&user_tokens).await,
+        10_000_000 - 1_010_000
+    );
+    assert!(harness.token_balance(&token_pool.vault).await >= user_stake.staked_amount);
+}
+
+#[tokio::test]
+async fn token_pool_rejects_unsupported_mint_extensions() {
+    let mut harness = Harness::new().await;
+    let token_program = spl_token_2022::ID;
+    let authority = harness.authority();
+
+    // Permanent delegate could move tokens out of the vault
+    let mint = harness
+        .create_mint(
+            &token_program,
+            6,
+            &[ExtensionType::PermanentDelegate],
+            |mint| {
+                vec![spl_token_2022::instruction::initialize_permanent_delegate(
+                    &token_program,
+                    mint,
+                    &authority,
+                )
+                .unwrap()]
+            },
+        )
+        .await;
+    let result = harness.create_token_pool(&mint, &token_program).await;
+    assert_error(result.map(|_| ()), StakingError::UnsupportedMint);
+
+    // Default-frozen accounts could lock stakers out of withdrawals
+    let mint = harness
+        .create_mint(
+            &token_program,
+            6,
+            &[ExtensionType::DefaultAccountState],
+            |mint| {
+                vec![
+                    spl_token_2022::extension::default_account_state::instruction::initialize_default_account_state(
+                        &token_program,
+                        mint,
+                        &spl_token_2022::state::AccountState::Frozen,
+                    )
+                    .unwrap(),
+                ]
+            },
+        )
+        .await;
+    let result = harness.create_token_pool(&mint, &token_program).await;
+    assert_error(result.map(|_| ()), StakingError::UnsupportedMint);
+
+    // The pause authority could block all transfers out of the vault
+    let mint = harness
+        .create_mint(&token_program, 6, &[ExtensionType::Pausable], |mint| {
+            vec![
+                spl_token_2022::extension::pausable::instruction::initialize(
+                    &token_program,
+                    mint,
+                    &authority,
+                )
+                .unwrap(),
+            ]
+        })
+        .await;
+    let result = harness.create_token_pool(&mint, &token_program).await;
+    assert_error(result.map(|_| ()), StakingError::UnsupportedMint);
+
+    // A plain Token-2022 mint is accepted
+    let mint = harness
+        .create_mint(&token_program, 6, &[], |_| vec![])
+        .await;
+    harness
+        .create_token_pool(&mint, &token_program)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use staking_express::errors::StakingError;
+use staking_express::state::*;
+
+const COOLDOWN: i64 = 7 * 24 * 60 * 60;
+
+#[tokio::test]
+async fn request_unstake_then_withdraw_after_cooldown() {
+    let mut harness = Harness::new().await;
+    let pool = staking_pool_pda(0);
+
+    let accounts = harness.pool_config_accounts(&pool);
+    let data = staking_express::instruction::UpdateUnstakeCooldown {
+        cooldown_seconds: COOLDOWN,
+    };
+    harness.send(&[ix(accounts, data)], &[]).await.unwrap();
+
+    let user = harness.new_user(20 * LAMPORTS_PER_SOL).await;
+    harness
+        .stake(&pool, &user, 10 * LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    // Instant unstake is disabled while the pool has a cooldown
+    let result = harness.unstake(&pool, &user, LAMPORTS_PER_SOL).await;
+    assert_error(result, StakingError::CooldownRequired);
+
+    let position: UserStakeState = harness
+        .account(&user_stake_pda(&user.pubkey(), &pool))
+        .await;
+
+    let accounts = harness.unstake_accounts(&pool, &user.pubkey());
+    let data = staking_express::instruction::RequestUnstake {
+        amount: position.staked_amount,
+    };
+    harness.send(&[ix(accounts, data)], &[&user]).await.unwrap();
+
+    // The queued net amount is tracked on the pool and the position
+    let queued: StakingPool = harness.account(&pool).await;
+    let position: UserStakeState = harness
+        .account(&user_stake_pda(&user.pubkey(), &pool))
+        .await;
+    assert!(position.pending_unstake > 0);
+    assert_eq!(position.staked_amount, 0);
+    assert_eq!(queued.total_pending_unstake, position.pending_unstake);
+    assert_eq!(queued.total_staked, 0);
+
+    let withdraw = ix(
+        staking_express::accounts::WithdrawUnstaked {
+            user: user.pubkey(),
+            global_config: global_config_pda(),
+            staking_pool: pool,
+            user_stake: user_stake_pda(&user.pubkey(), &pool),
+            mint: None,
+            user_token_account: None,
+            vault: None,
+            token_program: None,
+        },
+        staking_express::instruction::WithdrawUnstaked {},
+    );
+
+    // Not withdrawable before the cooldown elapses
+    let result = harness
+        .send(std::slice::from_ref(&withdraw), &[&user])
+        .await;
+    assert_error(result, StakingError::CooldownNotElapsed);
+
+    harness.advance_time(COOLDOWN).await;
+    let balance = harness.lamports(&user.pubkey()).await;
+    harness.send(&[withdraw], &[&user]).await.unwrap();
+
+    let after: StakingPool = harness.account(&pool).await;
+    let withdrawn: UserStakeState = harness
+        .account(&user_stake_pda(&user.pubkey(), &pool))
+        .await;
+    assert_eq!(after.total_pending_unstake, 0);
+    assert_eq!(withdrawn.pending_unstake, 0);
+    assert_eq!(
+        harness.lamports(&user.pubkey()).await,
+        balance + position.pending_unstake
+    );
+}
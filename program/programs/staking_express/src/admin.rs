@@ -0,0 +1,91 @@
+use crate::errors::StakingError;
+use crate::events::*;
+use crate::helpers::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    /// Authority or guardian (the guardian may only pause)
+    #[account(
+        constraint = caller.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub caller: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        mut,
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = caller.key() == global_config.authority
+            || global_config.is_guardian(&caller.key()) @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        mut,
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+pub fn set_pause_flags_handler(ctx: Context<SetPaused>, flags: u8, paused: bool) -> Result<()> {
+    let caller = ctx.accounts.caller.key();
+    let global_config = &mut ctx.accounts.global_config;
+    apply_pause_flags(global_config, &caller, flags, paused)?;
+
+    emit!(PoolPauseToggled {
+        flags,
+        paused,
+        pause_flags: global_config.pause_flags,
+        caller,
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!(
+        "Pause flags {:#07b} {}; now {:#07b}",
+        flags,
+        if paused { "paused" } else { "unpaused" },
+        global_config.pause_flags
+    );
+    if global_config.is_paused(pause_flags::UNSTAKE) {
+        msg!("Unstaking paused (emergency withdrawals enabled)");
+    }
+
+    Ok(())
+}
+
+pub fn set_guardian_handler(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let old_guardian = global_config.guardian;
+    global_config.guardian = guardian;
+
+    emit!(GuardianUpdated {
+        old_guardian,
+        new_guardian: guardian,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    if guardian == Pubkey::default() {
+        msg!("Guardian removed");
+    } else {
+        msg!("Guardian set to {}", guardian);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use crate::constants::*;
+use crate::errors::StakingError;
+use crate::events::*;
+use crate::helpers::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct AddAllowlistEntry<'info> {
+    /// Authority only (admin-controlled; pays for the entry)
+    #[account(
+        mut,
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Allowlist entry of the user
+    #[account(
+        init,
+        payer = authority,
+        space = AllowlistEntry::LEN,
+        seeds = [seeds::ALLOWLIST, staking_pool.key().as_ref(), user.as_ref()],
+        bump
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_allowlist_entry_handler(ctx: Context<AddAllowlistEntry>, user: Pubkey) -> Result<()> {
+    let staking_pool_key = ctx.accounts.staking_pool.key();
+    let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+    let current_timestamp = get_current_timestamp()?;
+
+    allowlist_entry.staking_pool = staking_pool_key;
+    allowlist_entry.user = user;
+    allowlist_entry.created_at = current_timestamp;
+    allowlist_entry.bump = ctx.bumps.allowlist_entry;
+
+    emit!(AllowlistEntryAdded {
+        pool: staking_pool_key,
+        user,
+        authority: ctx.accounts.authority.key(),
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "User {} allowlisted for pool {}",
+        user,
+        ctx.accounts.staking_pool.pool_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowlistEntry<'info> {
+    /// Authority only (admin-controlled; receives the entry's rent)
+    #[account(
+        mut,
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Allowlist entry to close
+    #[account(
+        mut,
+        close = authority,
+        seeds = [seeds::ALLOWLIST, staking_pool.key().as_ref(), allowlist_entry.user.as_ref()],
+        bump = allowlist_entry.bump,
+        owner = crate::ID
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+}
+
+/// Remove a user from a pool's allowlist
+///
+/// Existing positions are unaffected; the user can no longer add to them.
+pub fn remove_allowlist_entry_handler(ctx: Context<RemoveAllowlistEntry>) -> Result<()> {
+    let user = ctx.accounts.allowlist_entry.user;
+
+    emit!(AllowlistEntryRemoved {
+        pool: ctx.accounts.staking_pool.key(),
+        user,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!(
+        "User {} removed from pool {} allowlist",
+        user,
+        ctx.accounts.staking_pool.pool_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistRoot<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        mut,
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+pub fn set_allowlist_root_handler(ctx: Context<SetAllowlistRoot>, root: [u8; 32]) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let old_root = global_config.allowlist_merkle_root;
+    global_config.allowlist_merkle_root = root;
+
+    emit!(AllowlistRootUpdated {
+        old_root,
+        new_root: root,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    if root == [0; 32] {
+        msg!("Allowlist Merkle root cleared");
+    } else {
+        msg!("Allowlist Merkle root updated");
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllowlistEntry<'info> {
+    /// Allowlisted wallet (pays for its entry)
+    #[account(
+        mut,
+        constraint = user.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub user: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Allowlist entry of the user
+    #[account(
+        init,
+        payer = user,
+        space = AllowlistEntry::LEN,
+        seeds = [seeds::ALLOWLIST, staking_pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the caller's allowlist entry from a Merkle proof of membership
+///
+/// The proof must lead from allowlist_leaf(user) to the root on GlobalConfig.
+pub fn claim_allowlist_entry_handler(
+    ctx: Context<ClaimAllowlistEntry>,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let root = ctx.accounts.global_config.allowlist_merkle_root;
+    let user = ctx.accounts.user.key();
+    require!(
+        root != [0; 32]
+            && proof.len() <= MAX_MERKLE_PROOF_LEN
+            && verify_merkle_proof(&root, allowlist_leaf(&user), &proof),
+        StakingError::InvalidAllowlistProof
+    );
+
+    let staking_pool_key = ctx.accounts.staking_pool.key();
+    let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+    let current_timestamp = get_current_timestamp()?;
+
+    allowlist_entry.staking_pool = staking_pool_key;
+    allowlist_entry.user = user;
+    allowlist_entry.created_at = current_timestamp;
+    allowlist_entry.bump = ctx.bumps.allowlist_entry;
+
+    emit!(AllowlistEntryClaimed {
+        pool: staking_pool_key,
+        user,
+        timestamp: current_timestamp,
+    });
+
+    msg!(
+        "User {} claimed allowlist entry for pool {}",
+        user,
+        ctx.accounts.staking_pool.pool_id
+    );
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
-use crate::constants::*;
 use crate::errors::StakingError;
 use crate::events::*;
 use crate::helpers::*;
 use crate::math::*;
 use crate::state::*;
+use crate::token_vault::*;
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct DistributeBonusPool<'info> {
@@ -20,14 +21,15 @@ pub struct DistributeBonusPool<'info> {
     #[account(
         seeds = [seeds::GLOBAL_CONFIG],
         bump = global_config.bump,
-        owner = crate::ID
+        owner = crate::ID,
+        constraint = !global_config.is_paused(pause_flags::BONUS_DISTRIBUTION) @ StakingError::PoolPaused
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
     /// Staking pool
     #[account(
         mut,
-        seeds = [seeds::STAKING_POOL],
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
         bump = staking_pool.bump,
         owner = crate::ID
     )]
@@ -36,86 +38,153 @@ pub struct DistributeBonusPool<'info> {
     /// Bonus pool
     #[account(
         mut,
-        seeds = [seeds::BONUS_POOL],
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
         bump = bonus_pool.bump,
         owner = crate::ID
     )]
     pub bonus_pool: Account<'info, BonusPool>,
 
+    /// Record of the round being closed
+    #[account(
+        init,
+        payer = caller,
+        space = BonusRound::LEN,
+        seeds = [
+            seeds::BONUS_ROUND,
+            bonus_pool.key().as_ref(),
+            bonus_pool.current_round.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bonus_round: Account<'info, BonusRound>,
+
     pub system_program: Program<'info, System>,
 }
 
+/// Remaining accounts: UserStakeState of each last-10 investor
 pub fn distribute_bonus_pool_handler(ctx: Context<DistributeBonusPool>) -> Result<()> {
+    let investor_stakes =
+        load_investor_stakes(ctx.remaining_accounts, &ctx.accounts.staking_pool.key())?;
     let staking_pool = &mut ctx.accounts.staking_pool;
     let bonus_pool = &mut ctx.accounts.bonus_pool;
+    let bonus_round = &mut ctx.accounts.bonus_round;
     let current_timestamp = get_current_timestamp()?;
 
-    // Check if distribution is due (either countdown expired OR 6h inactivity)
-    let countdown_expired = check_bonus_expiry(bonus_pool, current_timestamp);
-    let inactivity_trigger = check_inactivity_trigger(bonus_pool, current_timestamp);
-
+    // Check if distribution is due (round expired by countdown OR 6h inactivity)
     require!(
-        countdown_expired || inactivity_trigger,
+        refresh_bonus_round_state(bonus_pool, current_timestamp) == BonusRoundState::Expired,
         StakingError::BonusNotExpired
     );
+    let trigger =
+        bonus_expiry_trigger(bonus_pool, current_timestamp).ok_or(StakingError::BonusNotExpired)?;
+    let countdown_expired = trigger == BonusTrigger::Countdown;
 
     // Validate bonus pool has balance
     require!(bonus_pool.balance > 0, StakingError::BonusPoolEmpty);
 
     let total_to_distribute = bonus_pool.balance;
 
-    // Calculate distribution: 40% / 40% / 20%
-    let (to_last_ten, to_all_stakers, carry_forward) =
-        calculate_bonus_distribution(total_to_distribute)?;
+    // Only entries eligible under the last-10 policy and still backed by a
+    // large enough stake are considered
+    let eligible = revalidate_last_ten(
+        &eligible_last_ten(bonus_pool),
+        &investor_stakes,
+        last_ten_net_threshold(
+            bonus_pool.config.last_ten_threshold,
+            &staking_pool.fee_schedule,
+        )?,
+    )?;
+
+    // Anti-sniping: entries held too briefly or unstaked since lose their share
+    let mut forfeited = vec![false; eligible.len()];
+    if anti_sniping_enabled(bonus_pool) {
+        for (entry, is_forfeited) in eligible.iter().zip(forfeited.iter_mut()) {
+            let user_stake = investor_stakes
+                .iter()
+                .find(|stake| stake.user == entry.investor)
+                .ok_or(StakingError::MissingInvestorStake)?;
+
+            *is_forfeited =
+                is_last_ten_share_forfeited(bonus_pool, entry, user_stake, current_timestamp);
+        }
+    }
+
+    // ========== JACKPOT TO LAST INVESTOR ==========
+
+    // Grand-prize mode: the most recent qualifying investor takes the jackpot
+    // when the countdown expires
+    let jackpot_winner =
+        if bonus_pool.config.mode == BonusMode::LastInvestorJackpot && countdown_expired {
+            eligible
+                .iter()
+                .zip(forfeited.iter())
+                .rev()
+                .find(|(_, is_forfeited)| !**is_forfeited)
+                .map(|(entry, _)| entry.investor)
+        } else {
+            None
+        };
+
+    let (jackpot, remaining_bonus) = match jackpot_winner {
+        Some(winner) => {
+            let (jackpot, remaining_bonus) =
+                calculate_jackpot(total_to_distribute, &bonus_pool.config)?;
+            // Reserved in the BonusRound record; the winner pulls it with claim_jackpot
+            msg!("Jackpot of {} lamports reserved for {}", jackpot, winner);
+            (jackpot, remaining_bonus)
+        }
+        None => (0, total_to_distribute),
+    };
+
+    // Calculate distribution of the rest: 40% / 40% / 20% by default
+    let (last_ten_slice, to_all_stakers, mut carry_forward) =
+        calculate_bonus_distribution(remaining_bonus, &bonus_pool.config)?;
 
     // ========== DISTRIBUTE TO LAST 10 INVESTORS ==========
 
-    if to_last_ten > 0 && bonus_pool.investor_count > 0 {
-        // Calculate total invested by last-10
-        let mut total_invested: u64 = 0;
-        for i in 0..bonus_pool.investor_count as usize {
-            total_invested = safe_add(total_invested, bonus_pool.last_ten_investors[i].amount)?;
+    let (mut winners, winner_count, mut to_last_ten) =
+        calculate_last_ten_shares(&eligible, last_ten_slice)?;
+
+    for (winner, is_forfeited) in winners.iter_mut().zip(forfeited.iter()) {
+        if *is_forfeited {
+            to_last_ten = safe_sub(to_last_ten, winner.share)?;
+            winner.share = 0;
+            winner.forfeited = true;
         }
+    }
 
-        // Distribute pro-rata
-        if total_invested > 0 {
-            for i in 0..bonus_pool.investor_count as usize {
-                let investor = &bonus_pool.last_ten_investors[i];
-                if investor.amount > 0 {
-                    let share =
-                        calculate_pro_rata_share(investor.amount, total_invested, to_last_ten)?;
-
-                    if share > 0 {
-                        // Transfer to investor
-                        // Note: This simplified implementation transfers to last-10 directly
-                        // In production, you'd want investor PDAs or another mechanism
-                        msg!(
-                            "Would transfer {} lamports to investor {}",
-                            share,
-                            investor.investor
-                        );
-                        // Actual transfer would require investor accounts in remaining_accounts
-                    }
-                }
-            }
+    // Reserved in the BonusRound record; investors pull their shares with
+    // claim_last_ten_share
+    for winner in winners[..winner_count as usize].iter() {
+        if winner.share > 0 {
+            msg!(
+                "Last-10 share of {} lamports reserved for {}",
+                winner.share,
+                winner.investor
+            );
         }
     }
 
+    // Unpaid part of the slice (no eligible entries, rounding) carries forward
+    carry_forward = safe_add(carry_forward, safe_sub(last_ten_slice, to_last_ten)?)?;
+
     // ========== DISTRIBUTE TO ALL STAKERS ==========
 
     if to_all_stakers > 0 && staking_pool.total_staked > 0 {
         // Update reward_per_share for all stakers
         update_reward_per_share(staking_pool, to_all_stakers)?;
 
-        // Transfer from bonus pool to staking pool
-        **bonus_pool.to_account_info().try_borrow_mut_lamports()? -= to_all_stakers;
-        **staking_pool.to_account_info().try_borrow_mut_lamports()? += to_all_stakers;
+        // Transfer from bonus pool to staking pool (token pools share one vault)
+        if !staking_pool.is_token_pool() {
+            **bonus_pool.to_account_info().try_borrow_mut_lamports()? -= to_all_stakers;
+            **staking_pool.to_account_info().try_borrow_mut_lamports()? += to_all_stakers;
+        }
     }
 
     // ========== UPDATE BONUS POOL STATE ==========
 
     // Deduct distributed amounts
-    let total_distributed = safe_add(to_last_ten, to_all_stakers)?;
+    let total_distributed = safe_add(safe_add(jackpot, to_last_ten)?, to_all_stakers)?;
     bonus_pool.balance = safe_sub(bonus_pool.balance, total_distributed)?;
 
     // Carry forward should now be the remaining balance
@@ -124,34 +193,454 @@ pub fn distribute_bonus_pool_handler(ctx: Context<DistributeBonusPool>) -> Resul
         StakingError::InvalidCalculation
     );
 
-    // Reset countdown to 12 hours
-    bonus_pool.expiry_timestamp = current_timestamp
-        .checked_add(BONUS_INITIAL_COUNTDOWN)
-        .ok_or(StakingError::MathOverflow)?;
-    bonus_pool.last_investment_timestamp = current_timestamp;
+    // ========== RECORD CLOSED ROUND ==========
 
-    // NOTE: Do NOT clear last-10 list (persists until 1+ SOL deposit)
-    // Only reset count to prevent duplicate distribution
-    // bonus_pool.investor_count = 0; // Keeping existing list
+    let round = bonus_pool.current_round;
+    bonus_round.round = round;
+    bonus_round.trigger = trigger;
+    bonus_round.winners = winners;
+    bonus_round.winner_count = winner_count;
+    bonus_round.total_balance = total_to_distribute;
+    bonus_round.jackpot_winner = jackpot_winner;
+    bonus_round.jackpot = jackpot;
+    bonus_round.jackpot_claimed = false;
+    bonus_round.to_last_ten = to_last_ten;
+    bonus_round.to_all_stakers = to_all_stakers;
+    bonus_round.carried_forward = carry_forward;
+    bonus_round.start_timestamp = bonus_pool.round_start_timestamp;
+    bonus_round.expiry_timestamp = bonus_pool.expiry_timestamp;
+    bonus_round.end_timestamp = current_timestamp;
+    bonus_round.bump = ctx.bumps.bonus_round;
+
+    // Expired → Distributed; the next qualifying stake starts the next round
+    // with a fresh countdown (12 hours by default)
+    bonus_pool.current_round = round.checked_add(1).ok_or(StakingError::MathOverflow)?;
+    bonus_pool.round_state = BonusRoundState::Distributed;
+
+    // Persist keeps the last-10 list (until pushed out by 1+ SOL deposits);
+    // CurrentRoundOnly keeps it too but ignores entries older than the new round
+    if bonus_pool.last_ten_policy == LastTenPolicy::ClearOnDistribution {
+        clear_last_ten(bonus_pool);
+    }
 
     // ========== EMIT EVENT ==========
 
     emit!(BonusPoolExpired {
+        round,
         total_distributed,
+        jackpot_winner,
+        jackpot,
         to_last_ten,
         to_all_stakers,
         carried_forward: carry_forward,
-        last_ten_count: bonus_pool.investor_count,
-        countdown_reset_to: bonus_pool.expiry_timestamp,
+        last_ten_count: winner_count,
+        trigger,
         timestamp: current_timestamp,
     });
 
-    msg!("✅ Bonus pool distributed!");
+    msg!("✅ Bonus pool distributed! (round {})", round);
     msg!("Total distributed: {} lamports", total_distributed);
-    msg!("To last 10: {} lamports (40%)", to_last_ten);
-    msg!("To all stakers: {} lamports (40%)", to_all_stakers);
-    msg!("Carried forward: {} lamports (20%)", carry_forward);
-    msg!("Countdown reset: 12 hours");
+    msg!("Jackpot: {} lamports", jackpot);
+    msg!("To last 10: {} lamports", to_last_ten);
+    msg!("To all stakers: {} lamports", to_all_stakers);
+    msg!("Carried forward: {} lamports", carry_forward);
+    msg!(
+        "Next round starts on the next stake >= 1 SOL ({} second countdown)",
+        bonus_pool.config.initial_countdown
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimJackpot<'info> {
+    /// Jackpot winner recorded in the bonus round
+    #[account(
+        mut,
+        constraint = winner.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub winner: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = !global_config.is_paused(pause_flags::CLAIM) @ StakingError::PoolPaused
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool the bonus pool belongs to (signs token vault transfers)
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Bonus pool (SOL pools: holds the reserved jackpot lamports)
+    #[account(
+        mut,
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
+        bump = bonus_pool.bump,
+        owner = crate::ID
+    )]
+    pub bonus_pool: Account<'info, BonusPool>,
+
+    /// Closed round awarding the jackpot
+    #[account(
+        mut,
+        seeds = [
+            seeds::BONUS_ROUND,
+            bonus_pool.key().as_ref(),
+            bonus_round.round.to_le_bytes().as_ref()
+        ],
+        bump = bonus_round.bump,
+        owner = crate::ID,
+        constraint = bonus_round.jackpot_winner == Some(winner.key()) @ StakingError::Unauthorized
+    )]
+    pub bonus_round: Account<'info, BonusRound>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: winner's token account (receives the jackpot)
+    #[account(mut)]
+    pub winner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_jackpot_handler(ctx: Context<ClaimJackpot>) -> Result<()> {
+    let winner_key = ctx.accounts.winner.key();
+    let round = ctx.accounts.bonus_round.round;
+    let amount = ctx.accounts.bonus_round.jackpot;
+    let current_timestamp = get_current_timestamp()?;
+
+    require!(
+        amount > 0 && !ctx.accounts.bonus_round.jackpot_claimed,
+        StakingError::NoJackpotAvailable
+    );
+
+    // Transfer from bonus pool (token pools: the vault) to the winner
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts.transfer_jackpot_tokens(amount)?;
+    } else {
+        **ctx
+            .accounts
+            .bonus_pool
+            .to_account_info()
+            .try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .winner
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+    }
+
+    ctx.accounts.bonus_round.jackpot_claimed = true;
+
+    emit!(JackpotClaimed {
+        round,
+        winner: winner_key,
+        amount,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Jackpot claimed! (round {})", round);
+    msg!("Winner: {}", winner_key);
+    msg!("Amount: {} lamports", amount);
+
+    Ok(())
+}
+
+impl<'info> ClaimJackpot<'info> {
+    /// Pay a token jackpot from the vault
+    fn transfer_jackpot_tokens(&self, amount: u64) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let winner_token_account = validate_token_account(
+            self.winner_token_account.as_ref(),
+            &self.staking_pool.mint,
+            &self.winner.key(),
+        )?;
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        transfer_pool_tokens(
+            &tokens,
+            tokens.vault.to_account_info(),
+            winner_token_account.to_account_info(),
+            self.staking_pool.to_account_info(),
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimLastTenShare<'info> {
+    /// Last-10 investor recorded in the bonus round
+    #[account(
+        mut,
+        constraint = investor.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub investor: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = !global_config.is_paused(pause_flags::CLAIM) @ StakingError::PoolPaused
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool the bonus pool belongs to (signs token vault transfers)
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Bonus pool (SOL pools: holds the reserved last-10 lamports)
+    #[account(
+        mut,
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
+        bump = bonus_pool.bump,
+        owner = crate::ID
+    )]
+    pub bonus_pool: Account<'info, BonusPool>,
+
+    /// Closed round recording the investor's share
+    #[account(
+        mut,
+        seeds = [
+            seeds::BONUS_ROUND,
+            bonus_pool.key().as_ref(),
+            bonus_round.round.to_le_bytes().as_ref()
+        ],
+        bump = bonus_round.bump,
+        owner = crate::ID
+    )]
+    pub bonus_round: Account<'info, BonusRound>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: investor's token account (receives the share)
+    #[account(mut)]
+    pub investor_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_last_ten_share_handler(ctx: Context<ClaimLastTenShare>) -> Result<()> {
+    let investor_key = ctx.accounts.investor.key();
+    let round = ctx.accounts.bonus_round.round;
+    let current_timestamp = get_current_timestamp()?;
+
+    let bonus_round = &mut ctx.accounts.bonus_round;
+    let winner_count = bonus_round.winner_count as usize;
+    let amount = take_last_ten_shares(&mut bonus_round.winners[..winner_count], &investor_key)?;
+    require!(amount > 0, StakingError::NoLastTenShareAvailable);
+
+    // Transfer from bonus pool (token pools: the vault) to the investor
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts.transfer_last_ten_tokens(amount)?;
+    } else {
+        **ctx
+            .accounts
+            .bonus_pool
+            .to_account_info()
+            .try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .investor
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+    }
+
+    emit!(LastTenShareClaimed {
+        round,
+        investor: investor_key,
+        amount,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Last-10 share claimed! (round {})", round);
+    msg!("Investor: {}", investor_key);
+    msg!("Amount: {} lamports", amount);
+
+    Ok(())
+}
+
+impl<'info> ClaimLastTenShare<'info> {
+    /// Pay a token last-10 share from the vault
+    fn transfer_last_ten_tokens(&self, amount: u64) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let investor_token_account = validate_token_account(
+            self.investor_token_account.as_ref(),
+            &self.staking_pool.mint,
+            &self.investor.key(),
+        )?;
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        transfer_pool_tokens(
+            &tokens,
+            tokens.vault.to_account_info(),
+            investor_token_account.to_account_info(),
+            self.staking_pool.to_account_info(),
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+            amount,
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UpdateBonusSettingsParams {
+    /// How last-10 entries carry over between rounds
+    pub last_ten_policy: Option<LastTenPolicy>,
+
+    /// Refresh a repeat investor's existing slot instead of taking a new one
+    pub dedupe_last_ten: Option<bool>,
+
+    /// Maximum remaining countdown after an extension, in seconds (0 = uncapped)
+    pub max_countdown_seconds: Option<i64>,
+
+    /// Extend by 15 minutes per whole SOL instead of a flat extension
+    pub proportional_extension: Option<bool>,
+
+    /// Cap on a single proportional extension, in seconds
+    pub max_extension_seconds: Option<i64>,
+
+    /// Minimum time a last-10 entry must be held before it is paid (0 = disabled)
+    pub min_hold_seconds: Option<i64>,
+
+    /// Forfeit an entry's bonus share if the investor unstaked after entering
+    pub forfeit_on_unstake: Option<bool>,
+
+    /// Distribution ratios, durations and thresholds (replaced as a whole)
+    pub config: Option<BonusConfig>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBonusSettings<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Bonus pool
+    #[account(
+        mut,
+        seeds = [seeds::BONUS_POOL, bonus_pool.staking_pool.as_ref()],
+        bump = bonus_pool.bump,
+        owner = crate::ID
+    )]
+    pub bonus_pool: Account<'info, BonusPool>,
+}
+
+pub fn update_bonus_settings_handler(
+    ctx: Context<UpdateBonusSettings>,
+    params: UpdateBonusSettingsParams,
+) -> Result<()> {
+    let bonus_pool = &mut ctx.accounts.bonus_pool;
+
+    if let Some(last_ten_policy) = params.last_ten_policy {
+        bonus_pool.last_ten_policy = last_ten_policy;
+    }
+    if let Some(dedupe_last_ten) = params.dedupe_last_ten {
+        bonus_pool.dedupe_last_ten = dedupe_last_ten;
+    }
+    if let Some(max_countdown_seconds) = params.max_countdown_seconds {
+        require!(max_countdown_seconds >= 0, StakingError::InvalidBonusConfig);
+        bonus_pool.max_countdown_seconds = max_countdown_seconds;
+    }
+    if let Some(proportional_extension) = params.proportional_extension {
+        bonus_pool.proportional_extension = proportional_extension;
+    }
+    if let Some(max_extension_seconds) = params.max_extension_seconds {
+        require!(max_extension_seconds > 0, StakingError::InvalidBonusConfig);
+        bonus_pool.max_extension_seconds = max_extension_seconds;
+    }
+    if let Some(min_hold_seconds) = params.min_hold_seconds {
+        require!(min_hold_seconds >= 0, StakingError::InvalidBonusConfig);
+        bonus_pool.min_hold_seconds = min_hold_seconds;
+    }
+    if let Some(forfeit_on_unstake) = params.forfeit_on_unstake {
+        bonus_pool.forfeit_on_unstake = forfeit_on_unstake;
+    }
+    if let Some(config) = params.config {
+        validate_bonus_config(&config)?;
+        if config.max_last_ten != bonus_pool.config.max_last_ten {
+            resize_last_ten(bonus_pool, config.max_last_ten);
+        }
+        bonus_pool.config = config;
+    }
+
+    emit!(BonusSettingsUpdated {
+        last_ten_policy: bonus_pool.last_ten_policy,
+        dedupe_last_ten: bonus_pool.dedupe_last_ten,
+        max_countdown_seconds: bonus_pool.max_countdown_seconds,
+        proportional_extension: bonus_pool.proportional_extension,
+        max_extension_seconds: bonus_pool.max_extension_seconds,
+        min_hold_seconds: bonus_pool.min_hold_seconds,
+        forfeit_on_unstake: bonus_pool.forfeit_on_unstake,
+        config: bonus_pool.config,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!("Last-10 policy: {:?}", bonus_pool.last_ten_policy);
+    msg!("Last-10 dedupe: {}", bonus_pool.dedupe_last_ten);
+    msg!(
+        "Max countdown: {}s, proportional extension: {} (max {}s)",
+        bonus_pool.max_countdown_seconds,
+        bonus_pool.proportional_extension,
+        bonus_pool.max_extension_seconds
+    );
+    msg!(
+        "Min hold: {}s, forfeit on unstake: {}",
+        bonus_pool.min_hold_seconds,
+        bonus_pool.forfeit_on_unstake
+    );
+    msg!("Bonus config: {:?}", bonus_pool.config);
 
     Ok(())
 }
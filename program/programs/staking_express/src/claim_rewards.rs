@@ -3,7 +3,9 @@ use crate::events::*;
 use crate::helpers::*;
 use crate::math::*;
 use crate::state::*;
+use crate::token_vault::*;
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
@@ -18,14 +20,14 @@ pub struct ClaimRewards<'info> {
         seeds = [seeds::GLOBAL_CONFIG],
         bump = global_config.bump,
         owner = crate::ID,
-        constraint = !global_config.paused @ StakingError::PoolPaused
+        constraint = !global_config.is_paused(pause_flags::CLAIM) @ StakingError::PoolPaused
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
     /// Staking pool
     #[account(
         mut,
-        seeds = [seeds::STAKING_POOL],
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
         bump = staking_pool.bump,
         owner = crate::ID
     )]
@@ -41,32 +43,74 @@ pub struct ClaimRewards<'info> {
     )]
     pub user_stake: Account<'info, UserStakeState>,
 
+    /// Reward recipient (required if the position has one; receives SOL rewards)
+    /// CHECK: Validated against user_stake.reward_recipient and owner checked
+    #[account(
+        mut,
+        owner = anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub reward_recipient: Option<UncheckedAccount<'info>>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: user's token account (receives the rewards without a reward recipient)
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: reward recipient's token account (receives the rewards)
+    #[account(mut)]
+    pub reward_recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn claim_rewards_handler(ctx: Context<ClaimRewards>) -> Result<()> {
     let user_key = ctx.accounts.user.key();
-    let staking_pool = &mut ctx.accounts.staking_pool;
-    let user_stake = &mut ctx.accounts.user_stake;
     let current_timestamp = get_current_timestamp()?;
 
     // Calculate pending rewards
-    let pending_rewards = get_pending_rewards(user_stake, staking_pool)?;
+    let pending_rewards =
+        get_pending_rewards(&ctx.accounts.user_stake, &ctx.accounts.staking_pool)?;
 
     // Validate rewards available
     require!(pending_rewards > 0, StakingError::NoRewardsAvailable);
 
-    // Transfer rewards to user (NO FEE)
-    **staking_pool.to_account_info().try_borrow_mut_lamports()? -= pending_rewards;
-    **ctx
-        .accounts
-        .user
-        .to_account_info()
-        .try_borrow_mut_lamports()? += pending_rewards;
+    // Transfer rewards to the user or their reward recipient (NO FEE)
+    let recipient = ctx.accounts.user_stake.reward_destination();
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts.transfer_reward_tokens(pending_rewards)?;
+    } else {
+        require_pool_liquidity(&ctx.accounts.staking_pool, pending_rewards)?;
+        **ctx
+            .accounts
+            .staking_pool
+            .to_account_info()
+            .try_borrow_mut_lamports()? -= pending_rewards;
+        let reward_recipient = reward_recipient_account(
+            &ctx.accounts.user_stake,
+            &ctx.accounts.user,
+            &ctx.accounts.reward_recipient,
+            pending_rewards,
+        )?;
+        **reward_recipient.try_borrow_mut_lamports()? += pending_rewards;
+    }
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
 
-    // Update reward debt to prevent double claims
+    // Drop an expired lockup boost, then update reward debt to prevent double claims
+    user_stake.accrued_rewards = 0;
+    refresh_weighted_shares(user_stake, staking_pool, current_timestamp)?;
     user_stake.reward_debt =
-        calculate_reward_debt(user_stake.staked_amount, staking_pool.reward_per_share)?;
+        calculate_reward_debt(user_stake.weighted_shares, staking_pool.reward_per_share)?;
 
     // Update last claim timestamp
     user_stake.last_claim_timestamp = current_timestamp;
@@ -74,6 +118,7 @@ pub fn claim_rewards_handler(ctx: Context<ClaimRewards>) -> Result<()> {
     // Emit event
     emit!(RewardsClaimed {
         user: user_key,
+        recipient,
         amount: pending_rewards,
         reward_debt_after: user_stake.reward_debt,
         timestamp: current_timestamp,
@@ -82,6 +127,118 @@ pub fn claim_rewards_handler(ctx: Context<ClaimRewards>) -> Result<()> {
     msg!("✅ Rewards claimed successfully!");
     msg!("User: {}", user_key);
     msg!("Rewards: {} lamports", pending_rewards);
+    if recipient != user_key {
+        msg!("Paid to reward recipient: {}", recipient);
+    }
+
+    Ok(())
+}
+
+impl<'info> ClaimRewards<'info> {
+    /// Pay token rewards from the vault
+    fn transfer_reward_tokens(&self, amount: u64) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let reward_token_account = reward_token_account(
+            &self.user_stake,
+            &self.staking_pool.mint,
+            self.user_token_account.as_ref(),
+            self.reward_recipient_token_account.as_ref(),
+        )?;
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        transfer_pool_tokens(
+            &tokens,
+            tokens.vault.to_account_info(),
+            reward_token_account.to_account_info(),
+            self.staking_pool.to_account_info(),
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetRewardRecipient<'info> {
+    pub user: Signer<'info>,
+
+    /// Staking pool
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// User's stake state
+    #[account(
+        mut,
+        seeds = [seeds::USER_STAKE, user.key().as_ref(), staking_pool.key().as_ref()],
+        bump = user_stake.bump,
+        owner = crate::ID,
+        constraint = user_stake.user == user.key() @ StakingError::Unauthorized
+    )]
+    pub user_stake: Account<'info, UserStakeState>,
+
+    /// New reward recipient (required unless clearing it)
+    /// CHECK: Must match the reward_recipient argument; owner checked
+    #[account(
+        owner = anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub reward_recipient: Option<UncheckedAccount<'info>>,
+}
+
+/// Set (or clear with Pubkey::default()) the wallet a position's rewards are paid to
+///
+/// The recipient must be a funded system-owned wallet, so SOL payouts never
+/// leave it below the rent-exempt minimum.
+pub fn set_reward_recipient_handler(
+    ctx: Context<SetRewardRecipient>,
+    reward_recipient: Pubkey,
+) -> Result<()> {
+    let user_key = ctx.accounts.user.key();
+    if reward_recipient != Pubkey::default() && reward_recipient != user_key {
+        let recipient = ctx
+            .accounts
+            .reward_recipient
+            .as_ref()
+            .ok_or(StakingError::InvalidRewardRecipient)?;
+        require!(
+            recipient.key() == reward_recipient,
+            StakingError::InvalidRewardRecipient
+        );
+        require!(
+            recipient.lamports() >= Rent::get()?.minimum_balance(0),
+            StakingError::RewardRecipientNotFunded
+        );
+    }
+
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    // Paying the owner is the default
+    user_stake.reward_recipient = if reward_recipient == user_key {
+        Pubkey::default()
+    } else {
+        reward_recipient
+    };
+
+    emit!(RewardRecipientUpdated {
+        user: user_key,
+        pool: ctx.accounts.staking_pool.key(),
+        reward_recipient: user_stake.reward_recipient,
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!(
+        "Rewards of {} now paid to {}",
+        user_key,
+        user_stake.reward_destination()
+    );
 
     Ok(())
 }
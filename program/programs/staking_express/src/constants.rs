@@ -4,16 +4,21 @@
 /// Lamports per SOL (1 SOL = 1_000_000_000 lamports)
 pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
 
+/// Decimals of native SOL (receipt tokens of SOL pools use the same)
+pub const SOL_DECIMALS: u8 = 9;
+
 /// Minimum stake amount in lamports (0.01 SOL)
+/// Token pools scale it to the mint's decimals (0.01 token)
 pub const MIN_STAKE: u64 = 10_000_000; // 0.01 SOL
 
 /// Minimum unstake amount in lamports (0.01 SOL)
+/// Token pools scale it to the mint's decimals (0.01 token)
 pub const MIN_UNSTAKE: u64 = 10_000_000; // 0.01 SOL
 
-/// Threshold for bonus pool countdown extension (1 SOL)
+/// Threshold for bonus pool countdown extension (1 SOL; 1 whole token for token pools)
 pub const BONUS_EXTENSION_THRESHOLD: u64 = LAMPORTS_PER_SOL; // 1 SOL
 
-/// Threshold for adding investor to last-10 list (1 SOL)
+/// Default threshold for adding investor to last-10 list (1 SOL; 1 whole token for token pools)
 pub const BONUS_LAST_TEN_THRESHOLD: u64 = LAMPORTS_PER_SOL; // 1 SOL
 
 /// Fee structure in basis points (total = 1000 BPS = 10%)
@@ -35,24 +40,73 @@ pub const FEE_MATERIAL_DART_BPS: u64 = 50;
 /// Total fee in basis points (must equal sum of all fee components)
 pub const TOTAL_FEE_BPS: u64 = 1000; // 10%
 
+/// Maximum total fee a pool's fee schedule may charge (20%)
+pub const MAX_TOTAL_FEE_BPS: u64 = 2000; // 20%
+
 /// Basis points denominator (100% = 10000 BPS)
 pub const BPS_DENOMINATOR: u64 = 10000;
 
-/// Bonus pool initial countdown duration in seconds (12 hours)
+/// Default bonus pool countdown duration in seconds (12 hours)
 pub const BONUS_INITIAL_COUNTDOWN: i64 = 43200; // 12 hours
 
 /// Bonus pool countdown extension per 1 SOL stake (15 minutes)
 pub const BONUS_EXTENSION_SECONDS: i64 = 900; // 15 minutes
 
-/// Inactivity trigger threshold (6 hours with no investment)
+/// Default cap on remaining countdown after an extension (24 hours ahead of now)
+pub const BONUS_MAX_COUNTDOWN: i64 = 86400; // 24 hours
+
+/// Default cap on a single proportional extension (4 hours = 16 SOL)
+pub const BONUS_MAX_EXTENSION_SECONDS: i64 = 14400; // 4 hours
+
+/// Default inactivity trigger threshold (6 hours with no investment)
 pub const BONUS_INACTIVITY_THRESHOLD: i64 = 21600; // 6 hours
 
-/// Maximum investors tracked in last-10 circular buffer
+/// Maximum investors tracked in last-10 circular buffer (buffer capacity)
 pub const MAX_LAST_TEN_INVESTORS: usize = 10;
 
+/// Default bonus distribution: 40% to last-10 investors
+pub const BONUS_LAST_TEN_BPS: u16 = 4000; // 40%
+
+/// Default bonus distribution: 40% to all stakers
+pub const BONUS_STAKERS_BPS: u16 = 4000; // 40%
+
+/// Default bonus distribution: 20% carried forward
+pub const BONUS_CARRY_FORWARD_BPS: u16 = 2000; // 20%
+
 /// Referral pool distribution period (30 days)
 pub const REFERRAL_DISTRIBUTION_PERIOD: i64 = 2592000; // 30 days
 
+/// Share of each referral distribution paid to all stakers (50%)
+pub const REFERRAL_STAKERS_BPS: u64 = 5000; // 50%
+
+/// Maximum referrer share of a referral distribution (the carried-forward half)
+pub const MAX_REFERRER_SHARE_BPS: u16 = 5000; // 50%
+
+/// Lockup tier durations
+pub const LOCKUP_30_DAYS: i64 = 2592000; // 30 days
+pub const LOCKUP_90_DAYS: i64 = 7776000; // 90 days
+pub const LOCKUP_180_DAYS: i64 = 15552000; // 180 days
+
+/// Reward weight multipliers of the lockup tiers (10000 BPS = 1x)
+pub const LOCKUP_NONE_MULTIPLIER_BPS: u64 = 10000; // 1x
+pub const LOCKUP_30_DAYS_MULTIPLIER_BPS: u64 = 12500; // 1.25x
+pub const LOCKUP_90_DAYS_MULTIPLIER_BPS: u64 = 15000; // 1.5x
+pub const LOCKUP_180_DAYS_MULTIPLIER_BPS: u64 = 20000; // 2x
+
+/// Maximum unstake cooldown of a pool
+pub const MAX_UNSTAKE_COOLDOWN: i64 = 2592000; // 30 days
+
+/// Maximum share of a pool's total_staked delegated to validators
+/// (the rest is kept as a liquidity buffer for unstakes and claims)
+pub const MAX_DELEGATION_TARGET_BPS: u16 = 9000; // 90%
+
+/// Maximum depth of an allowlist Merkle proof (2^20 wallets)
+pub const MAX_MERKLE_PROOF_LEN: usize = 20;
+
+/// Domain prefixes of allowlist Merkle leaves and internal nodes
+pub const MERKLE_LEAF_PREFIX: u8 = 0;
+pub const MERKLE_NODE_PREFIX: u8 = 1;
+
 /// Precision for reward calculations (1e12 to avoid rounding errors)
 pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
 
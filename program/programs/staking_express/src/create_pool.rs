@@ -0,0 +1,348 @@
+use crate::constants::*;
+use crate::errors::StakingError;
+use crate::events::*;
+use crate::fees::*;
+use crate::helpers::*;
+use crate::math::*;
+use crate::state::*;
+use crate::token_vault::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        mut,
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration (shared admin root for all pools)
+    #[account(
+        mut,
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// New staking pool (next pool_id)
+    #[account(
+        init,
+        payer = authority,
+        space = StakingPool::LEN,
+        seeds = [seeds::STAKING_POOL, global_config.pool_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Bonus pool for the new staking pool
+    #[account(
+        init,
+        payer = authority,
+        space = BonusPool::LEN,
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
+        bump
+    )]
+    pub bonus_pool: Account<'info, BonusPool>,
+
+    /// Referral pool for the new staking pool
+    #[account(
+        init,
+        payer = authority,
+        space = ReferralPool::LEN,
+        seeds = [seeds::REFERRAL_POOL, staking_pool.key().as_ref()],
+        bump
+    )]
+    pub referral_pool: Account<'info, ReferralPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_pool_handler(ctx: Context<CreatePool>, fee_schedule: FeeSchedule) -> Result<()> {
+    validate_fee_schedule(&fee_schedule)?;
+
+    let authority = ctx.accounts.authority.key();
+    let global_config = &mut ctx.accounts.global_config;
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let bonus_pool = &mut ctx.accounts.bonus_pool;
+    let referral_pool = &mut ctx.accounts.referral_pool;
+    let current_timestamp = get_current_timestamp()?;
+
+    let pool_id = global_config.pool_count;
+    let staking_pool_key = staking_pool.key();
+
+    init_staking_pool(
+        staking_pool,
+        global_config.key(),
+        pool_id,
+        fee_schedule,
+        ctx.bumps.staking_pool,
+        current_timestamp,
+    );
+    init_bonus_pool(
+        bonus_pool,
+        staking_pool_key,
+        ctx.bumps.bonus_pool,
+        current_timestamp,
+    )?;
+    init_referral_pool(
+        referral_pool,
+        staking_pool_key,
+        ctx.bumps.referral_pool,
+        current_timestamp,
+    )?;
+
+    global_config.pool_count = safe_add(pool_id, 1)?;
+
+    emit!(PoolCreated {
+        pool: staking_pool_key,
+        pool_id,
+        bonus_pool: bonus_pool.key(),
+        referral_pool: referral_pool.key(),
+        fee_schedule,
+        mint: Pubkey::default(),
+        authority,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Staking pool {} created", pool_id);
+    msg!("Pool: {}", staking_pool_key);
+    msg!("Total fee: {} BPS", fee_schedule.total_bps());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateTokenPool<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        mut,
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration (shared admin root for all pools)
+    #[account(
+        mut,
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// New staking pool (next pool_id)
+    #[account(
+        init,
+        payer = authority,
+        space = StakingPool::LEN,
+        seeds = [seeds::STAKING_POOL, global_config.pool_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Bonus pool for the new staking pool
+    #[account(
+        init,
+        payer = authority,
+        space = BonusPool::LEN,
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
+        bump
+    )]
+    pub bonus_pool: Account<'info, BonusPool>,
+
+    /// Referral pool for the new staking pool
+    #[account(
+        init,
+        payer = authority,
+        space = ReferralPool::LEN,
+        seeds = [seeds::REFERRAL_POOL, staking_pool.key().as_ref()],
+        bump
+    )]
+    pub referral_pool: Account<'info, ReferralPool>,
+
+    /// Token the pool is denominated in
+    #[account(
+        constraint = *mint.to_account_info().owner == token_program.key() @ StakingError::InvalidTokenAccount
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Pool vault (authority = staking pool PDA)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::VAULT, staking_pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = staking_pool,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token or Token-2022 program
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_token_pool_handler(
+    ctx: Context<CreateTokenPool>,
+    fee_schedule: FeeSchedule,
+) -> Result<()> {
+    validate_fee_schedule(&fee_schedule)?;
+    validate_pool_mint(&ctx.accounts.mint.to_account_info())?;
+
+    let authority = ctx.accounts.authority.key();
+    let mint = ctx.accounts.mint.key();
+    let decimals = ctx.accounts.mint.decimals;
+    let vault = ctx.accounts.vault.key();
+    let token_program = ctx.accounts.token_program.key();
+    let global_config = &mut ctx.accounts.global_config;
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let bonus_pool = &mut ctx.accounts.bonus_pool;
+    let referral_pool = &mut ctx.accounts.referral_pool;
+    let current_timestamp = get_current_timestamp()?;
+
+    let pool_id = global_config.pool_count;
+    let staking_pool_key = staking_pool.key();
+
+    init_staking_pool(
+        staking_pool,
+        global_config.key(),
+        pool_id,
+        fee_schedule,
+        ctx.bumps.staking_pool,
+        current_timestamp,
+    );
+    staking_pool.mint = mint;
+    staking_pool.vault = vault;
+    staking_pool.token_program = token_program;
+    staking_pool.decimals = decimals;
+    init_bonus_pool(
+        bonus_pool,
+        staking_pool_key,
+        ctx.bumps.bonus_pool,
+        current_timestamp,
+    )?;
+    // Default last-10 threshold is 1 whole token rather than 1e9 base units
+    bonus_pool.config.last_ten_threshold = scale_to_decimals(BONUS_LAST_TEN_THRESHOLD, decimals)?;
+    init_referral_pool(
+        referral_pool,
+        staking_pool_key,
+        ctx.bumps.referral_pool,
+        current_timestamp,
+    )?;
+
+    global_config.pool_count = safe_add(pool_id, 1)?;
+
+    emit!(PoolCreated {
+        pool: staking_pool_key,
+        pool_id,
+        bonus_pool: bonus_pool.key(),
+        referral_pool: referral_pool.key(),
+        fee_schedule,
+        mint,
+        authority,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Token staking pool {} created", pool_id);
+    msg!("Pool: {}", staking_pool_key);
+    msg!("Mint: {}", mint);
+    msg!("Vault: {}", vault);
+    msg!("Total fee: {} BPS", fee_schedule.total_bps());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct CreateReceiptMint<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        mut,
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool (must be empty and not yet liquid)
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID,
+        constraint = !staking_pool.is_liquid() @ StakingError::AlreadyInitialized,
+        constraint = staking_pool.total_staked == 0 && staking_pool.total_stakers == 0 @ StakingError::PoolNotEmpty
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Token pools: the pool's asset mint (receipt decimals must match)
+    pub pool_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Receipt token mint (mint authority = staking pool PDA)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::RECEIPT_MINT, staking_pool.key().as_ref()],
+        bump,
+        mint::decimals = decimals,
+        mint::authority = staking_pool,
+        mint::token_program = token_program
+    )]
+    pub receipt_mint: InterfaceAccount<'info, Mint>,
+
+    /// Token or Token-2022 program for the receipt mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_receipt_mint_handler(ctx: Context<CreateReceiptMint>, decimals: u8) -> Result<()> {
+    let staking_pool = &mut ctx.accounts.staking_pool;
+
+    // Shares start 1:1 with the pool asset, so they share its decimals
+    let asset_decimals = if staking_pool.is_token_pool() {
+        let pool_mint = ctx
+            .accounts
+            .pool_mint
+            .as_ref()
+            .ok_or(StakingError::InvalidTokenAccount)?;
+        require!(
+            pool_mint.key() == staking_pool.mint,
+            StakingError::InvalidTokenAccount
+        );
+        pool_mint.decimals
+    } else {
+        SOL_DECIMALS
+    };
+    require!(decimals == asset_decimals, StakingError::InvalidReceiptMint);
+
+    let receipt_mint = ctx.accounts.receipt_mint.key();
+    staking_pool.receipt_mint = receipt_mint;
+    staking_pool.total_shares = 0;
+
+    emit!(ReceiptMintCreated {
+        pool: staking_pool.key(),
+        receipt_mint,
+        decimals,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!("✅ Receipt token enabled for pool {}", staking_pool.pool_id);
+    msg!("Receipt mint: {}", receipt_mint);
+
+    Ok(())
+}
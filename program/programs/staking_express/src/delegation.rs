@@ -0,0 +1,656 @@
+use crate::errors::StakingError;
+use crate::events::*;
+use crate::helpers::*;
+use crate::math::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program::{allocate, assign, create_account, transfer};
+use anchor_lang::system_program::{Allocate, Assign, CreateAccount, Transfer};
+use solana_stake_interface::instruction as stake_instruction;
+use solana_stake_interface::program::ID as STAKE_PROGRAM_ID;
+use solana_stake_interface::state::{Authorized, Lockup, StakeStateV2};
+use solana_stake_interface::tools::get_minimum_delegation;
+
+/// Stake history sysvar (required by delegate and withdraw)
+const STAKE_HISTORY_ID: Pubkey = pubkey!("SysvarStakeHistory1111111111111111111111111");
+
+/// Stake config account (still required by DelegateStake for compatibility)
+const STAKE_CONFIG_ID: Pubkey = pubkey!("StakeConfig11111111111111111111111111111111");
+
+#[derive(Accounts)]
+pub struct UpdateDelegationConfig<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool (SOL pools only)
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID,
+        constraint = !staking_pool.is_token_pool() @ StakingError::DelegationNotSupported
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+}
+
+pub fn update_delegation_config_handler(
+    ctx: Context<UpdateDelegationConfig>,
+    delegation_target_bps: u16,
+) -> Result<()> {
+    validate_delegation_target(delegation_target_bps)?;
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    staking_pool.delegation_target_bps = delegation_target_bps;
+
+    emit!(DelegationConfigUpdated {
+        pool: staking_pool.key(),
+        delegation_target_bps,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!(
+        "Pool {} delegation target set to {} BPS",
+        staking_pool.pool_id,
+        delegation_target_bps
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DelegateStake<'info> {
+    /// Authority only (pays the stake account rent reserve)
+    #[account(
+        mut,
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool (SOL pools only; staker and withdrawer of the stake account)
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID,
+        constraint = !staking_pool.is_token_pool() @ StakingError::DelegationNotSupported
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Record of the new stake account (next stake account index)
+    #[account(
+        init,
+        payer = authority,
+        space = ValidatorStake::LEN,
+        seeds = [
+            seeds::VALIDATOR_STAKE,
+            staking_pool.key().as_ref(),
+            staking_pool.stake_account_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub validator_stake: Account<'info, ValidatorStake>,
+
+    /// CHECK: New native stake account (PDA, created by this instruction)
+    #[account(
+        mut,
+        seeds = [
+            seeds::STAKE_ACCOUNT,
+            staking_pool.key().as_ref(),
+            staking_pool.stake_account_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validator vote account (validated by the stake program)
+    pub vote_account: UncheckedAccount<'info>,
+
+    /// CHECK: Stake history sysvar
+    #[account(address = STAKE_HISTORY_ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: Stake config account
+    #[account(address = STAKE_CONFIG_ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    /// CHECK: Native stake program
+    #[account(address = STAKE_PROGRAM_ID)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn delegate_stake_handler(ctx: Context<DelegateStake>, amount: u64) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidAmount);
+    require!(
+        amount >= get_minimum_delegation()?,
+        StakingError::DelegationTooSmall
+    );
+
+    // Keep the liquidity buffer: never delegate beyond the pool's target
+    let capacity = calculate_delegation_capacity(
+        ctx.accounts.staking_pool.total_staked,
+        ctx.accounts.staking_pool.total_delegated,
+        ctx.accounts.staking_pool.delegation_target_bps,
+    )?;
+    require!(amount <= capacity, StakingError::DelegationLimitExceeded);
+    require_pool_liquidity(&ctx.accounts.staking_pool, amount)?;
+
+    let rent_reserve = ctx.accounts.rent.minimum_balance(StakeStateV2::size_of());
+    ctx.accounts
+        .create_stake_account(rent_reserve, ctx.bumps.stake_account)?;
+    ctx.accounts.initialize_stake_account()?;
+
+    // Move the delegated lamports out of the pool account (after the initialize CPI,
+    // so both sides of the move are visible to the delegate CPI)
+    **ctx
+        .accounts
+        .staking_pool
+        .to_account_info()
+        .try_borrow_mut_lamports()? -= amount;
+    **ctx
+        .accounts
+        .stake_account
+        .to_account_info()
+        .try_borrow_mut_lamports()? += amount;
+
+    ctx.accounts.delegate()?;
+
+    let staking_pool_key = ctx.accounts.staking_pool.key();
+    let stake_account_key = ctx.accounts.stake_account.key();
+    let vote_account_key = ctx.accounts.vote_account.key();
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let validator_stake = &mut ctx.accounts.validator_stake;
+    let current_timestamp = get_current_timestamp()?;
+
+    validator_stake.staking_pool = staking_pool_key;
+    validator_stake.stake_account = stake_account_key;
+    validator_stake.vote_account = vote_account_key;
+    validator_stake.index = staking_pool.stake_account_count;
+    validator_stake.delegated = amount;
+    validator_stake.rent_reserve = rent_reserve;
+    validator_stake.deactivated = false;
+    validator_stake.created_at = current_timestamp;
+    validator_stake.bump = ctx.bumps.validator_stake;
+    validator_stake.stake_account_bump = ctx.bumps.stake_account;
+
+    staking_pool.total_delegated = safe_add(staking_pool.total_delegated, amount)?;
+    staking_pool.stake_account_count = safe_add(staking_pool.stake_account_count, 1)?;
+
+    emit!(StakeDelegated {
+        pool: staking_pool_key,
+        validator_stake: validator_stake.key(),
+        stake_account: stake_account_key,
+        vote_account: vote_account_key,
+        amount,
+        total_delegated: staking_pool.total_delegated,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Delegated {} lamports", amount);
+    msg!("Stake account: {}", stake_account_key);
+    msg!("Validator: {}", vote_account_key);
+    msg!("Total delegated: {} lamports", staking_pool.total_delegated);
+
+    Ok(())
+}
+
+impl<'info> DelegateStake<'info> {
+    /// Create the stake account PDA with its rent-exempt reserve (paid by the authority)
+    ///
+    /// Lamports sent to the address beforehand are kept (recognized as rewards on harvest)
+    fn create_stake_account(&self, rent_reserve: u64, stake_account_bump: u8) -> Result<()> {
+        let staking_pool_key = self.staking_pool.key();
+        let index = self.staking_pool.stake_account_count.to_le_bytes();
+        let bump = [stake_account_bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            seeds::STAKE_ACCOUNT,
+            staking_pool_key.as_ref(),
+            index.as_ref(),
+            &bump,
+        ]];
+        let system_program = self.system_program.to_account_info();
+        let authority = self.authority.to_account_info();
+        let stake_account = self.stake_account.to_account_info();
+        let space = StakeStateV2::size_of() as u64;
+
+        let existing_lamports = stake_account.lamports();
+        if existing_lamports == 0 {
+            return create_account(
+                CpiContext::new_with_signer(
+                    system_program,
+                    CreateAccount {
+                        from: authority,
+                        to: stake_account,
+                    },
+                    signer_seeds,
+                ),
+                rent_reserve,
+                space,
+                &STAKE_PROGRAM_ID,
+            );
+        }
+
+        if existing_lamports < rent_reserve {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: authority,
+                        to: stake_account.clone(),
+                    },
+                ),
+                rent_reserve - existing_lamports,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: stake_account.clone(),
+                },
+                signer_seeds,
+            ),
+            space,
+        )?;
+        assign(
+            CpiContext::new_with_signer(
+                system_program,
+                Assign {
+                    account_to_assign: stake_account,
+                },
+                signer_seeds,
+            ),
+            &STAKE_PROGRAM_ID,
+        )
+    }
+
+    /// Initialize the stake account (staker = withdrawer = staking pool PDA)
+    fn initialize_stake_account(&self) -> Result<()> {
+        let staking_pool_key = self.staking_pool.key();
+        let authorized = Authorized {
+            staker: staking_pool_key,
+            withdrawer: staking_pool_key,
+        };
+
+        invoke_signed(
+            &stake_instruction::initialize(
+                &self.stake_account.key(),
+                &authorized,
+                &Lockup::default(),
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.rent.to_account_info(),
+            ],
+            &[],
+        )?;
+
+        Ok(())
+    }
+
+    /// Delegate the stake account to the vote account
+    fn delegate(&self) -> Result<()> {
+        let staking_pool_key = self.staking_pool.key();
+        let stake_account_key = self.stake_account.key();
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        invoke_signed(
+            &stake_instruction::delegate_stake(
+                &stake_account_key,
+                &staking_pool_key,
+                &self.vote_account.key(),
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.vote_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_config.to_account_info(),
+                self.staking_pool.to_account_info(),
+            ],
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct HarvestStakeRewards<'info> {
+    /// Staking pool
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Validator stake record
+    #[account(
+        mut,
+        seeds = [
+            seeds::VALIDATOR_STAKE,
+            staking_pool.key().as_ref(),
+            validator_stake.index.to_le_bytes().as_ref()
+        ],
+        bump = validator_stake.bump,
+        owner = crate::ID
+    )]
+    pub validator_stake: Account<'info, ValidatorStake>,
+
+    /// CHECK: Stake account of the record
+    #[account(
+        address = validator_stake.stake_account @ StakingError::InvalidStakeAccount
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+}
+
+/// Recognize epoch rewards credited to a stake account (callable by anyone)
+///
+/// Rewards stay delegated (compounding) and are paid to stakers from the
+/// pool's liquidity buffer.
+pub fn harvest_stake_rewards_handler(ctx: Context<HarvestStakeRewards>) -> Result<()> {
+    let stake_value = ctx
+        .accounts
+        .stake_account
+        .lamports()
+        .saturating_sub(ctx.accounts.validator_stake.rent_reserve);
+    let rewards = stake_value.saturating_sub(ctx.accounts.validator_stake.delegated);
+    require!(rewards > 0, StakingError::NoRewardsAvailable);
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let validator_stake = &mut ctx.accounts.validator_stake;
+
+    update_reward_per_share(staking_pool, rewards)?;
+    validator_stake.delegated = safe_add(validator_stake.delegated, rewards)?;
+    staking_pool.total_delegated = safe_add(staking_pool.total_delegated, rewards)?;
+
+    emit!(StakeRewardsHarvested {
+        pool: staking_pool.key(),
+        stake_account: validator_stake.stake_account,
+        rewards,
+        total_delegated: staking_pool.total_delegated,
+        reward_per_share_after: staking_pool.reward_per_share,
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!("✅ Harvested {} lamports of validator rewards", rewards);
+    msg!("Stake account: {}", validator_stake.stake_account);
+
+    Ok(())
+}
+
+/// Whether `caller` may deactivate and withdraw validator stakes
+///
+/// Normally the authority only; while unstake is paused anyone may, so users
+/// can return delegated SOL to the pool and exit through emergency_withdraw.
+fn can_unwind_delegation(global_config: &GlobalConfig, caller: &Pubkey) -> bool {
+    *caller == global_config.authority || global_config.is_paused(pause_flags::UNSTAKE)
+}
+
+#[derive(Accounts)]
+pub struct DeactivateStake<'info> {
+    /// Authority, or anyone while unstake is paused
+    #[account(
+        constraint = caller.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub caller: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = can_unwind_delegation(&global_config, &caller.key()) @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool (stake authority)
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Validator stake record
+    #[account(
+        mut,
+        seeds = [
+            seeds::VALIDATOR_STAKE,
+            staking_pool.key().as_ref(),
+            validator_stake.index.to_le_bytes().as_ref()
+        ],
+        bump = validator_stake.bump,
+        owner = crate::ID,
+        constraint = !validator_stake.deactivated @ StakingError::StakeAlreadyDeactivated
+    )]
+    pub validator_stake: Account<'info, ValidatorStake>,
+
+    /// CHECK: Stake account of the record
+    #[account(
+        mut,
+        address = validator_stake.stake_account @ StakingError::InvalidStakeAccount
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: Native stake program
+    #[account(address = STAKE_PROGRAM_ID)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn deactivate_stake_handler(ctx: Context<DeactivateStake>) -> Result<()> {
+    let staking_pool = &ctx.accounts.staking_pool;
+    let pool_id = staking_pool.pool_id.to_le_bytes();
+    let bump = [staking_pool.bump];
+
+    invoke_signed(
+        &stake_instruction::deactivate_stake(
+            &ctx.accounts.stake_account.key(),
+            &staking_pool.key(),
+        ),
+        &[
+            ctx.accounts.stake_account.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            staking_pool.to_account_info(),
+        ],
+        &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+    )?;
+
+    let validator_stake = &mut ctx.accounts.validator_stake;
+    validator_stake.deactivated = true;
+
+    emit!(StakeDeactivated {
+        pool: staking_pool.key(),
+        stake_account: validator_stake.stake_account,
+        delegated: validator_stake.delegated,
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!("✅ Stake deactivation started");
+    msg!("Stake account: {}", validator_stake.stake_account);
+    msg!(
+        "Withdrawable after cooldown: {} lamports",
+        validator_stake.delegated
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    /// Authority, or anyone while unstake is paused
+    #[account(
+        constraint = caller.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub caller: Signer<'info>,
+
+    /// CHECK: Protocol authority (paid the rent reserve, receives it back)
+    #[account(
+        mut,
+        address = global_config.authority @ StakingError::Unauthorized
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = can_unwind_delegation(&global_config, &caller.key()) @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool (withdraw authority; receives principal and rewards)
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Validator stake record (closed after withdrawal)
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            seeds::VALIDATOR_STAKE,
+            staking_pool.key().as_ref(),
+            validator_stake.index.to_le_bytes().as_ref()
+        ],
+        bump = validator_stake.bump,
+        owner = crate::ID,
+        constraint = validator_stake.deactivated @ StakingError::StakeNotDeactivated
+    )]
+    pub validator_stake: Account<'info, ValidatorStake>,
+
+    /// CHECK: Stake account of the record
+    #[account(
+        mut,
+        address = validator_stake.stake_account @ StakingError::InvalidStakeAccount
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: Stake history sysvar
+    #[account(address = STAKE_HISTORY_ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: Native stake program
+    #[account(address = STAKE_PROGRAM_ID)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Withdraw a fully deactivated stake account back into the pool
+///
+/// Principal and unharvested rewards return to the pool's liquidity; the rent
+/// reserve returns to the authority and the record is closed. The stake program
+/// rejects the withdrawal until the cooldown has completed.
+pub fn withdraw_stake_handler(ctx: Context<WithdrawStake>) -> Result<()> {
+    let stake_lamports = ctx.accounts.stake_account.lamports();
+    let rent_reserve = ctx.accounts.validator_stake.rent_reserve;
+    let delegated = ctx.accounts.validator_stake.delegated;
+
+    let amount = stake_lamports.saturating_sub(rent_reserve);
+    let rent_returned = stake_lamports - amount;
+    let rewards = amount.saturating_sub(delegated);
+    let shortfall = delegated.saturating_sub(amount);
+
+    ctx.accounts
+        .withdraw_to(ctx.accounts.staking_pool.to_account_info(), amount)?;
+    ctx.accounts
+        .withdraw_to(ctx.accounts.authority.to_account_info(), rent_returned)?;
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let stake_account = ctx.accounts.validator_stake.stake_account;
+    let current_timestamp = get_current_timestamp()?;
+
+    staking_pool.total_delegated = safe_sub(staking_pool.total_delegated, delegated)?;
+    update_reward_per_share(staking_pool, rewards)?;
+    staking_pool.last_update_timestamp = current_timestamp;
+
+    emit!(StakeWithdrawn {
+        pool: staking_pool.key(),
+        stake_account,
+        amount,
+        rewards,
+        shortfall,
+        rent_returned,
+        total_delegated: staking_pool.total_delegated,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Stake withdrawn: {} lamports", amount);
+    msg!("Stake account: {}", stake_account);
+    msg!("Rewards: {} lamports", rewards);
+    if shortfall > 0 {
+        msg!("Shortfall: {} lamports", shortfall);
+    }
+    msg!("Total delegated: {} lamports", staking_pool.total_delegated);
+
+    Ok(())
+}
+
+impl<'info> WithdrawStake<'info> {
+    /// Withdraw lamports from the stake account (signed by the staking pool PDA)
+    fn withdraw_to(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        invoke_signed(
+            &stake_instruction::withdraw(
+                &self.stake_account.key(),
+                &self.staking_pool.key(),
+                &to.key(),
+                amount,
+                None,
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                to,
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.staking_pool.to_account_info(),
+            ],
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+        )?;
+
+        Ok(())
+    }
+}
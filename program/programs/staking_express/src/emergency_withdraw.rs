@@ -0,0 +1,209 @@
+use crate::errors::StakingError;
+use crate::events::*;
+use crate::helpers::*;
+use crate::math::*;
+use crate::state::*;
+use crate::token_vault::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        constraint = user.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub user: Signer<'info>,
+
+    /// Global configuration (only while unstaking is paused)
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = global_config.is_paused(pause_flags::UNSTAKE) @ StakingError::ProtocolNotPaused
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// User's stake state (created for holders of transferred receipt tokens)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserStakeState::LEN,
+        seeds = [seeds::USER_STAKE, user.key().as_ref(), staking_pool.key().as_ref()],
+        bump,
+        constraint = user_stake.user == user.key() || user_stake.user == Pubkey::default() @ StakingError::Unauthorized
+    )]
+    pub user_stake: Account<'info, UserStakeState>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: user's token account (receives the withdrawal)
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Liquid pools: receipt token mint
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Liquid pools: user's receipt token account (entire balance is redeemed)
+    #[account(mut)]
+    pub user_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Liquid pools: token program of the receipt mint
+    pub receipt_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Return a position's principal while unstaking is paused
+///
+/// No fee is charged and unclaimed rewards are forfeited (they stay in the pool).
+/// Lockups and the unstake cooldown do not apply.
+pub fn emergency_withdraw_handler(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+    let user_key = ctx.accounts.user.key();
+    let liquid = ctx.accounts.staking_pool.is_liquid();
+    let current_timestamp = get_current_timestamp()?;
+
+    // Liquid pools redeem the holder's entire receipt balance at the share price
+    let (staked_amount, shares_burned) = if liquid {
+        let receipt = receipt_token_accounts(
+            &ctx.accounts.staking_pool,
+            &user_key,
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.user_receipt_account,
+            &ctx.accounts.receipt_token_program,
+        )?;
+        let shares = receipt.token_account.amount;
+        let assets = calculate_assets_for_shares(
+            shares,
+            ctx.accounts.staking_pool.total_staked,
+            ctx.accounts.staking_pool.total_shares,
+        )?;
+        burn_receipt_tokens(&receipt, ctx.accounts.user.to_account_info(), shares)?;
+        (assets, shares)
+    } else {
+        (ctx.accounts.user_stake.staked_amount, 0)
+    };
+
+    let pending_unstake = ctx.accounts.user_stake.pending_unstake;
+    let rewards_forfeited = if liquid {
+        0
+    } else {
+        get_pending_rewards(&ctx.accounts.user_stake, &ctx.accounts.staking_pool)?
+    };
+    let amount = safe_add(staked_amount, pending_unstake)?;
+    require!(amount > 0, StakingError::NoStakePosition);
+
+    // ========== TRANSFER PRINCIPAL (NO FEE) ==========
+
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts.transfer_emergency_tokens(amount)?;
+    } else {
+        // Delegated lamports must be withdrawn first (anyone may while unstake is paused)
+        let staking_pool = ctx.accounts.staking_pool.to_account_info();
+        require!(
+            pool_liquidity(&staking_pool)? >= amount,
+            StakingError::InsufficientLiquidity
+        );
+        **staking_pool.try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .user
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+    }
+
+    // ========== ZERO THE POSITION ==========
+
+    let staking_pool_key = ctx.accounts.staking_pool.key();
+    let user_stake_bump = ctx.bumps.user_stake;
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    if user_stake.user == Pubkey::default() {
+        user_stake.user = user_key;
+        user_stake.pool = staking_pool_key;
+        user_stake.referrer = None;
+        user_stake.bump = user_stake_bump;
+        user_stake.reward_recipient = Pubkey::default();
+    }
+
+    user_stake.staked_amount = 0;
+    user_stake.lockup_tier = LockupTier::None;
+    user_stake.unlock_timestamp = 0;
+    refresh_weighted_shares(user_stake, staking_pool, current_timestamp)?;
+    user_stake.reward_debt = 0;
+    user_stake.accrued_rewards = 0;
+    user_stake.pending_unstake = 0;
+    user_stake.unstake_ready_timestamp = 0;
+    user_stake.last_unstake_timestamp = current_timestamp;
+
+    staking_pool.total_staked = safe_sub(staking_pool.total_staked, staked_amount)?;
+    staking_pool.total_shares = safe_sub(staking_pool.total_shares, shares_burned)?;
+    staking_pool.total_pending_unstake =
+        safe_sub(staking_pool.total_pending_unstake, pending_unstake)?;
+    staking_pool.last_update_timestamp = current_timestamp;
+
+    emit!(EmergencyWithdrawn {
+        user: user_key,
+        pool: staking_pool_key,
+        amount,
+        pending_unstake,
+        rewards_forfeited,
+        shares_burned,
+        total_staked_after: staking_pool.total_staked,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Emergency withdrawal successful!");
+    msg!("User: {}", user_key);
+    msg!("Withdrawn: {} lamports (NO FEE)", amount);
+    msg!("Rewards forfeited: {} lamports", rewards_forfeited);
+
+    Ok(())
+}
+
+impl<'info> EmergencyWithdraw<'info> {
+    /// Pay the principal of a token position from the vault
+    fn transfer_emergency_tokens(&self, amount: u64) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let user_token_account = validate_token_account(
+            self.user_token_account.as_ref(),
+            &self.staking_pool.mint,
+            &self.user.key(),
+        )?;
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        transfer_pool_tokens(
+            &tokens,
+            tokens.vault.to_account_info(),
+            user_token_account.to_account_info(),
+            self.staking_pool.to_account_info(),
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+            amount,
+        )
+    }
+}
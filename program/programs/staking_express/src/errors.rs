@@ -30,7 +30,7 @@ pub enum StakingError {
     #[msg("No rewards available to claim")]
     NoRewardsAvailable,
 
-    #[msg("Invalid fee breakdown - components do not sum to 1000 BPS")]
+    #[msg("Invalid fee breakdown - components do not match the fee schedule")]
     InvalidFeeBreakdown,
 
     #[msg("Invalid amount - must be greater than zero")]
@@ -39,8 +39,41 @@ pub enum StakingError {
     #[msg("Invalid timestamp")]
     InvalidTimestamp,
 
+    #[msg("Invalid referral configuration - referrer share exceeds carried-forward half")]
+    InvalidReferralConfig,
+
+    #[msg("Invalid bonus configuration")]
+    InvalidBonusConfig,
+
+    #[msg("Invalid fee schedule - total fee exceeds maximum")]
+    InvalidFeeSchedule,
+
+    #[msg("Invalid delegation target - exceeds maximum share of total staked")]
+    InvalidDelegationTarget,
+
+    #[msg("Delegation exceeds the pool's delegation target")]
+    DelegationLimitExceeded,
+
+    #[msg("Stake would exceed the pool's TVL cap")]
+    PoolCapExceeded,
+
+    #[msg("Stake would exceed the per-user stake cap")]
+    UserCapExceeded,
+
+    #[msg("Delegation is below the stake program minimum")]
+    DelegationTooSmall,
+
+    #[msg("Invalid unstake fee curve")]
+    InvalidUnstakeFeeCurve,
+
+    #[msg("Invalid unstake cooldown - exceeds maximum")]
+    InvalidUnstakeCooldown,
+
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+
     // ==================== State Errors (6030-6049) ====================
-    #[msg("Staking pool is paused")]
+    #[msg("This operation is paused")]
     PoolPaused,
 
     #[msg("Bonus pool countdown has not expired")]
@@ -64,10 +97,73 @@ pub enum StakingError {
     #[msg("Circular buffer is full")]
     CircularBufferFull,
 
+    #[msg("No referral rewards available to claim")]
+    NoReferralRewardsAvailable,
+
+    #[msg("Missing stake account for a last-10 investor")]
+    MissingInvestorStake,
+
+    #[msg("Invalid bonus round state for this operation")]
+    InvalidBonusRoundState,
+
+    #[msg("Pool already has stakers")]
+    PoolNotEmpty,
+
+    #[msg("Insufficient undelegated liquidity - stake must be deactivated and withdrawn first")]
+    InsufficientLiquidity,
+
+    #[msg("Validator stake is already deactivated")]
+    StakeAlreadyDeactivated,
+
+    #[msg("Validator stake must be deactivated before withdrawal")]
+    StakeNotDeactivated,
+
+    #[msg("Delegation is only supported by SOL pools")]
+    DelegationNotSupported,
+
+    #[msg("Stake is locked until its unlock timestamp")]
+    StakeLocked,
+
+    #[msg("Cannot reduce the lockup tier of a locked position")]
+    LockupDowngrade,
+
+    #[msg("Lockups are not supported by liquid pools")]
+    LockupNotSupported,
+
+    #[msg("Lockup has not expired")]
+    LockupNotExpired,
+
+    #[msg("Pool has an unstake cooldown - use request_unstake")]
+    CooldownRequired,
+
+    #[msg("Unstake cooldown has not elapsed")]
+    CooldownNotElapsed,
+
+    #[msg("No pending unstake to withdraw")]
+    NoPendingUnstake,
+
+    #[msg("Emergency withdrawal is only available while unstaking is paused")]
+    ProtocolNotPaused,
+
+    #[msg("No unclaimed jackpot for this round")]
+    NoJackpotAvailable,
+
+    #[msg("No unclaimed last-10 share for this investor in this round")]
+    NoLastTenShareAvailable,
+
     // ==================== Authorization Errors (6050-6059) ====================
     #[msg("Unauthorized: caller is not the authority")]
     Unauthorized,
 
+    #[msg("Unauthorized: only the authority can unpause")]
+    GuardianCannotUnpause,
+
+    #[msg("Staker is not on the pool's allowlist")]
+    NotAllowlisted,
+
+    #[msg("Allowlist proof does not match the Merkle root")]
+    InvalidAllowlistProof,
+
     #[msg("Invalid authority account")]
     InvalidAuthority,
 
@@ -77,6 +173,12 @@ pub enum StakingError {
     #[msg("Invalid Material Dart wallet")]
     InvalidMaterialDartWallet,
 
+    #[msg("Invalid referrer account")]
+    InvalidReferrer,
+
+    #[msg("Stakers cannot refer themselves")]
+    SelfReferral,
+
     // ==================== Account Errors (6060-6069) ====================
     #[msg("Invalid PDA derivation")]
     InvalidPDA,
@@ -89,4 +191,25 @@ pub enum StakingError {
 
     #[msg("Invalid account owner")]
     InvalidAccountOwner,
+
+    #[msg("Invalid or missing token account for this pool")]
+    InvalidTokenAccount,
+
+    #[msg("Mint extensions not supported by token pools")]
+    UnsupportedMint,
+
+    #[msg("Invalid receipt mint - decimals must match the pool's asset")]
+    InvalidReceiptMint,
+
+    #[msg("Invalid stake account for this validator stake")]
+    InvalidStakeAccount,
+
+    #[msg("Beneficiary must be given for stake_for and omitted for stake")]
+    InvalidBeneficiary,
+
+    #[msg("Reward recipient account does not match the position's reward recipient")]
+    InvalidRewardRecipient,
+
+    #[msg("Reward recipient must be a rent-exempt wallet")]
+    RewardRecipientNotFunded,
 }
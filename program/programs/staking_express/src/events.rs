@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 
+use crate::state::{
+    BonusConfig, BonusTrigger, FeeSchedule, LastTenPolicy, LockupTier, UnstakeFeeCurve,
+};
+
 /// Event emitted when the protocol is initialized
 #[event]
 pub struct ProtocolInitialized {
@@ -9,12 +13,37 @@ pub struct ProtocolInitialized {
     pub timestamp: i64,
 }
 
+/// Event emitted when a staking pool is created
+#[event]
+pub struct PoolCreated {
+    pub pool: Pubkey,
+    pub pool_id: u64,
+    pub bonus_pool: Pubkey,
+    pub referral_pool: Pubkey,
+    pub fee_schedule: FeeSchedule,
+    pub mint: Pubkey, // Pubkey::default() for SOL pools
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pool switches to liquid receipt tokens
+#[event]
+pub struct ReceiptMintCreated {
+    pub pool: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub decimals: u8,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
 /// Event emitted when a user stakes SOL
 #[event]
 pub struct Staked {
-    pub user: Pubkey,
+    pub user: Pubkey,   // Position owner
+    pub funder: Pubkey, // Signer that paid the stake (differs for stake_for)
+    pub pool: Pubkey,
     pub gross_amount: u64,         // Amount before fees
-    pub net_amount: u64,           // Amount after 10% fee
+    pub net_amount: u64,           // Amount after fees (10% by default)
     pub fee_to_stakers: u64,       // 700 BPS
     pub fee_to_platform: u64,      // 100 BPS
     pub fee_to_bonus: u64,         // 100 BPS
@@ -23,6 +52,11 @@ pub struct Staked {
     pub referrer: Option<Pubkey>,
     pub total_staked_after: u64,
     pub reward_per_share_after: u128,
+    pub shares_minted: u64,   // Receipt shares (liquid pools)
+    pub accrued_rewards: u64, // Unclaimed rewards carried over (paid by claim/unstake)
+    pub lockup_tier: LockupTier,
+    pub unlock_timestamp: i64,
+    pub weighted_shares: u64, // User's reward weight after the stake
     pub timestamp: i64,
 }
 
@@ -30,14 +64,51 @@ pub struct Staked {
 #[event]
 pub struct Unstaked {
     pub user: Pubkey,
+    pub pool: Pubkey,
     pub gross_amount: u64,    // Amount before fees
-    pub net_amount: u64,      // Amount after 10% fee
+    pub net_amount: u64,      // Amount after fees (10% by default)
     pub rewards_claimed: u64, // Rewards transferred (no fee)
+    pub reward_recipient: Pubkey,
+    pub shares_burned: u64, // Receipt shares (liquid pools)
     pub fee_to_stakers: u64,
     pub fee_to_platform: u64,
     pub fee_to_bonus: u64,
     pub fee_to_referral: u64,
     pub fee_to_material_dart: u64,
+    pub fee_bps: u64, // Effective total unstake fee (after age decay)
+    pub total_staked_after: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an unstake is queued behind the pool's cooldown
+#[event]
+pub struct UnstakeRequested {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,          // Net amount queued by this request
+    pub pending_total: u64,   // User's total queued amount
+    pub ready_timestamp: i64, // Withdrawable from (applies to the whole queued amount)
+    pub timestamp: i64,
+}
+
+/// Event emitted when a queued unstake is withdrawn
+#[event]
+pub struct UnstakeWithdrawn {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a user withdraws their principal while the protocol is paused
+#[event]
+pub struct EmergencyWithdrawn {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64, // Principal returned (no fee), including queued unstakes
+    pub pending_unstake: u64, // Queued unstake included in amount
+    pub rewards_forfeited: u64, // Unclaimed rewards left in the pool
+    pub shares_burned: u64, // Receipt shares (liquid pools)
     pub total_staked_after: u64,
     pub timestamp: i64,
 }
@@ -46,27 +117,78 @@ pub struct Unstaked {
 #[event]
 pub struct RewardsClaimed {
     pub user: Pubkey,
+    pub recipient: Pubkey, // Wallet paid (owner or reward recipient)
     pub amount: u64,
     pub reward_debt_after: u128,
     pub timestamp: i64,
 }
 
+/// Event emitted when an expired lockup's boost is dropped
+#[event]
+pub struct LockupRefreshed {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub lockup_tier: LockupTier, // Expired tier
+    pub weighted_shares: u64,    // Reward weight after the refresh (1x)
+    pub accrued_rewards: u64,    // Rewards carried over, still claimable
+    pub timestamp: i64,
+}
+
+/// Event emitted when a position's reward recipient is set or cleared
+#[event]
+pub struct RewardRecipientUpdated {
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub reward_recipient: Pubkey, // Pubkey::default() = the owner
+    pub timestamp: i64,
+}
+
 /// Event emitted when bonus pool expires and distributes
 #[event]
 pub struct BonusPoolExpired {
+    pub round: u64,
     pub total_distributed: u64,
+    pub jackpot_winner: Option<Pubkey>, // LastInvestorJackpot mode
+    pub jackpot: u64,
     pub to_last_ten: u64,     // 40%
     pub to_all_stakers: u64,  // 40%
     pub carried_forward: u64, // 20%
     pub last_ten_count: u8,
-    pub countdown_reset_to: i64,
+    pub trigger: BonusTrigger, // Countdown or inactivity
+    pub timestamp: i64,
+}
+
+/// Event emitted when the jackpot winner claims a round's jackpot
+#[event]
+pub struct JackpotClaimed {
+    pub round: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a last-10 investor claims their share of a round
+#[event]
+pub struct LastTenShareClaimed {
+    pub round: u64,
+    pub investor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a qualifying stake starts a new bonus round
+#[event]
+pub struct BonusRoundStarted {
+    pub round: u64,
+    pub expiry_timestamp: i64,
+    pub staker: Pubkey,
     pub timestamp: i64,
 }
 
 /// Event emitted when bonus countdown is extended
 #[event]
 pub struct BonusCountdownExtended {
-    pub extended_by: i64, // Seconds added
+    pub extended_by: i64, // Effective seconds added (after caps)
     pub new_expiry: i64,
     pub staker: Pubkey,
     pub stake_amount: u64,
@@ -85,26 +207,204 @@ pub struct ReferralPaid {
 /// Event emitted when referral pool distributes monthly
 #[event]
 pub struct ReferralPoolDistributed {
+    pub epoch: u64,
     pub total_distributed: u64,
     pub to_stakers: u64,      // 50%
-    pub carried_forward: u64, // 50%
+    pub to_referrers: u64,    // referrer_share_bps (pro-rata to referred volume)
+    pub carried_forward: u64, // remainder
     pub next_distribution: i64,
     pub timestamp: i64,
 }
 
+/// Event emitted when a referrer registers for volume tracking
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a referrer claims their referral pool share
+#[event]
+pub struct ReferrerRewardsClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the referral distribution mode is updated
+#[event]
+pub struct ReferralConfigUpdated {
+    pub referrer_share_bps: u16,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when bonus pool settings are updated
+#[event]
+pub struct BonusSettingsUpdated {
+    pub last_ten_policy: LastTenPolicy,
+    pub dedupe_last_ten: bool,
+    pub max_countdown_seconds: i64,
+    pub proportional_extension: bool,
+    pub max_extension_seconds: i64,
+    pub min_hold_seconds: i64,
+    pub forfeit_on_unstake: bool,
+    pub config: BonusConfig,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
 /// Event emitted when an investor is added to last-10 list
 #[event]
 pub struct InvestorAddedToLastTen {
     pub investor: Pubkey,
-    pub amount: u64,
+    pub amount: u64, // Net stake (pro-rata weight)
     pub position: u8,
+    pub updated_existing: bool, // Repeat investor's slot refreshed (dedupe mode)
     pub timestamp: i64,
 }
 
-/// Event emitted when pool is paused/unpaused
+/// Event emitted when operations are paused/unpaused
 #[event]
 pub struct PoolPauseToggled {
+    pub flags: u8, // Operations toggled by this call
     pub paused: bool,
+    pub pause_flags: u8, // Paused operations after the update
+    pub caller: Pubkey,  // Authority or guardian
+    pub timestamp: i64,
+}
+
+/// Event emitted when the guardian is set or removed
+#[event]
+pub struct GuardianUpdated {
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pool's allowlist mode is toggled
+#[event]
+pub struct AllowlistModeUpdated {
+    pub pool: Pubkey,
+    pub enabled: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a user is added to a pool's allowlist
+#[event]
+pub struct AllowlistEntryAdded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
+
+/// Event emitted when a user claims a pool's allowlist entry with a Merkle proof
+#[event]
+pub struct AllowlistEntryClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the allowlist Merkle root is set or cleared
+#[event]
+pub struct AllowlistRootUpdated {
+    pub old_root: [u8; 32],
+    pub new_root: [u8; 32], // [0; 32] = none
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a user is removed from a pool's allowlist
+#[event]
+pub struct AllowlistEntryRemoved {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pool's stake caps are updated
+#[event]
+pub struct StakeCapsUpdated {
+    pub pool: Pubkey,
+    pub max_total_staked: u64,   // 0 = no cap
+    pub max_stake_per_user: u64, // 0 = no cap
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pool's unstake fee curve is updated
+#[event]
+pub struct UnstakeFeeCurveUpdated {
+    pub pool: Pubkey,
+    pub curve: UnstakeFeeCurve,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pool's unstake cooldown is updated
+#[event]
+pub struct UnstakeCooldownUpdated {
+    pub pool: Pubkey,
+    pub cooldown_seconds: i64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a pool's delegation target is updated
+#[event]
+pub struct DelegationConfigUpdated {
+    pub pool: Pubkey,
+    pub delegation_target_bps: u16,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when pooled SOL is delegated to a validator
+#[event]
+pub struct StakeDelegated {
+    pub pool: Pubkey,
+    pub validator_stake: Pubkey,
+    pub stake_account: Pubkey,
+    pub vote_account: Pubkey,
+    pub amount: u64,
+    pub total_delegated: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when validator rewards are recognized for stakers
+#[event]
+pub struct StakeRewardsHarvested {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub rewards: u64,
+    pub total_delegated: u64,
+    pub reward_per_share_after: u128,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a validator stake is deactivated
+#[event]
+pub struct StakeDeactivated {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub delegated: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a deactivated stake is withdrawn back into the pool
+#[event]
+pub struct StakeWithdrawn {
+    pub pool: Pubkey,
+    pub stake_account: Pubkey,
+    pub amount: u64,        // Returned to the pool (principal plus rewards)
+    pub rewards: u64,       // Unharvested rewards recognized on withdrawal
+    pub shortfall: u64,     // Lamports below the recorded delegation (slashing)
+    pub rent_returned: u64, // Rent reserve returned to the authority
+    pub total_delegated: u64,
+    pub timestamp: i64,
+}
@@ -1,6 +1,7 @@
 use crate::constants::*;
 use crate::errors::StakingError;
 use crate::math::{safe_div, safe_mul};
+use crate::state::{FeeSchedule, UnstakeFeeCurve};
 use anchor_lang::prelude::*;
 
 /// Fee breakdown for stake/unstake operations
@@ -30,7 +31,7 @@ pub struct FeeBreakdown {
 
 /// Calculate fee breakdown for staking
 ///
-/// Applies 10% total fee with the following distribution:
+/// Applies the pool's fee schedule. The default schedule is a 10% total fee:
 /// - 700 BPS (7%) → Stakers (distributed via reward_per_share)
 /// - 100 BPS (1%) → Platform commission
 /// - 100 BPS (1%) → Bonus pool
@@ -38,15 +39,15 @@ pub struct FeeBreakdown {
 /// - 50 BPS (0.5%) → Material Dart team
 ///
 /// All calculations use floor rounding (no ceiling)
-pub fn calculate_stake_fee(gross_amount: u64) -> Result<FeeBreakdown> {
+pub fn calculate_stake_fee(gross_amount: u64, schedule: &FeeSchedule) -> Result<FeeBreakdown> {
     // Calculate each component using BPS
     // Formula: fee = (amount * bps) / BPS_DENOMINATOR
 
-    let stakers = calculate_bps_amount(gross_amount, FEE_STAKERS_BPS)?;
-    let platform = calculate_bps_amount(gross_amount, FEE_PLATFORM_BPS)?;
-    let bonus_pool = calculate_bps_amount(gross_amount, FEE_BONUS_POOL_BPS)?;
-    let referral = calculate_bps_amount(gross_amount, FEE_REFERRAL_BPS)?;
-    let material_dart = calculate_bps_amount(gross_amount, FEE_MATERIAL_DART_BPS)?;
+    let stakers = calculate_bps_amount(gross_amount, schedule.stakers_bps as u64)?;
+    let platform = calculate_bps_amount(gross_amount, schedule.platform_bps as u64)?;
+    let bonus_pool = calculate_bps_amount(gross_amount, schedule.bonus_pool_bps as u64)?;
+    let referral = calculate_bps_amount(gross_amount, schedule.referral_bps as u64)?;
+    let material_dart = calculate_bps_amount(gross_amount, schedule.material_dart_bps as u64)?;
 
     // Sum all fees
     let total_fee = stakers
@@ -56,9 +57,10 @@ pub fn calculate_stake_fee(gross_amount: u64) -> Result<FeeBreakdown> {
         .and_then(|sum| sum.checked_add(material_dart))
         .ok_or(StakingError::MathOverflow)?;
 
-    // Verify total fee is exactly 10%
-    let expected_total = calculate_bps_amount(gross_amount, TOTAL_FEE_BPS)?;
-    if total_fee != expected_total {
+    // Verify total fee matches the schedule total (floor rounding per component
+    // may leave the sum up to one lamport per component below it)
+    let expected_total = calculate_bps_amount(gross_amount, schedule.total_bps())?;
+    if total_fee > expected_total {
         // This should never happen with correct constants, but check anyway
         return Err(StakingError::InvalidFeeBreakdown.into());
     }
@@ -82,9 +84,90 @@ pub fn calculate_stake_fee(gross_amount: u64) -> Result<FeeBreakdown> {
 /// Calculate fee breakdown for unstaking
 ///
 /// Uses identical fee structure as staking
-pub fn calculate_unstake_fee(gross_amount: u64) -> Result<FeeBreakdown> {
+pub fn calculate_unstake_fee(gross_amount: u64, schedule: &FeeSchedule) -> Result<FeeBreakdown> {
     // Unstake uses same fee structure as stake
-    calculate_stake_fee(gross_amount)
+    calculate_stake_fee(gross_amount, schedule)
+}
+
+/// Fee schedule for an unstake of a position of the given age
+///
+/// Without an enabled curve this is the pool schedule; otherwise every component
+/// is scaled to the curve's effective total (floor rounding)
+pub fn unstake_fee_schedule(
+    schedule: &FeeSchedule,
+    curve: &UnstakeFeeCurve,
+    stake_age: i64,
+) -> Result<FeeSchedule> {
+    if !curve.enabled {
+        return Ok(*schedule);
+    }
+
+    let total_bps = schedule.total_bps();
+    let effective_bps = calculate_unstake_fee_bps(total_bps, curve, stake_age)?;
+    if total_bps == 0 || effective_bps == total_bps {
+        return Ok(*schedule);
+    }
+
+    let scale = |bps: u16| -> Result<u16> {
+        let scaled = safe_div(safe_mul(bps as u64, effective_bps)?, total_bps)?;
+        Ok(scaled as u16)
+    };
+
+    Ok(FeeSchedule {
+        stakers_bps: scale(schedule.stakers_bps)?,
+        platform_bps: scale(schedule.platform_bps)?,
+        bonus_pool_bps: scale(schedule.bonus_pool_bps)?,
+        referral_bps: scale(schedule.referral_bps)?,
+        material_dart_bps: scale(schedule.material_dart_bps)?,
+    })
+}
+
+/// Effective total unstake fee (BPS) on the decay curve
+///
+/// Formula (full_fee_seconds <= age < full_fee_seconds + decay_seconds):
+/// bps = total - (total - floor) * (age - full_fee_seconds) / decay_seconds
+pub fn calculate_unstake_fee_bps(
+    total_bps: u64,
+    curve: &UnstakeFeeCurve,
+    stake_age: i64,
+) -> Result<u64> {
+    let floor_bps = (curve.floor_bps as u64).min(total_bps);
+    if stake_age < curve.full_fee_seconds {
+        return Ok(total_bps);
+    }
+
+    let decayed = (stake_age - curve.full_fee_seconds) as u64;
+    let decay_seconds = curve.decay_seconds as u64;
+    if decayed >= decay_seconds {
+        return Ok(floor_bps);
+    }
+
+    let reduction = safe_div(safe_mul(total_bps - floor_bps, decayed)?, decay_seconds)?;
+    Ok(total_bps - reduction)
+}
+
+/// Validate an unstake fee curve
+pub fn validate_unstake_fee_curve(curve: &UnstakeFeeCurve) -> Result<()> {
+    require!(
+        curve.full_fee_seconds >= 0
+            && curve.decay_seconds >= 0
+            && curve.floor_bps as u64 <= MAX_TOTAL_FEE_BPS,
+        StakingError::InvalidUnstakeFeeCurve
+    );
+
+    Ok(())
+}
+
+/// Validate a pool fee schedule
+///
+/// Total fee must not exceed MAX_TOTAL_FEE_BPS
+pub fn validate_fee_schedule(schedule: &FeeSchedule) -> Result<()> {
+    require!(
+        schedule.total_bps() <= MAX_TOTAL_FEE_BPS,
+        StakingError::InvalidFeeSchedule
+    );
+
+    Ok(())
 }
 
 /// Calculate amount for a given basis points
@@ -123,7 +206,7 @@ mod tests {
     fn test_stake_fee_1_sol() {
         // 1 SOL = 1_000_000_000 lamports
         let amount = 1_000_000_000;
-        let fees = calculate_stake_fee(amount).unwrap();
+        let fees = calculate_stake_fee(amount, &FeeSchedule::default()).unwrap();
 
         // Expected breakdown:
         // 700 BPS = 70_000_000 (0.07 SOL)
@@ -145,7 +228,7 @@ mod tests {
     #[test]
     fn test_fee_sum_equals_total() {
         let amount = 1_000_000_000;
-        let fees = calculate_stake_fee(amount).unwrap();
+        let fees = calculate_stake_fee(amount, &FeeSchedule::default()).unwrap();
 
         let sum =
             fees.stakers + fees.platform + fees.bonus_pool + fees.referral + fees.material_dart;
@@ -156,8 +239,8 @@ mod tests {
     #[test]
     fn test_unstake_fee_matches_stake() {
         let amount = 1_000_000_000;
-        let stake_fees = calculate_stake_fee(amount).unwrap();
-        let unstake_fees = calculate_unstake_fee(amount).unwrap();
+        let stake_fees = calculate_stake_fee(amount, &FeeSchedule::default()).unwrap();
+        let unstake_fees = calculate_unstake_fee(amount, &FeeSchedule::default()).unwrap();
 
         assert_eq!(stake_fees.stakers, unstake_fees.stakers);
         assert_eq!(stake_fees.platform, unstake_fees.platform);
@@ -170,7 +253,7 @@ mod tests {
     fn test_small_amount_floor_rounding() {
         // Test with 1 lamport - should round down to 0 for all fees
         let amount = 1;
-        let fees = calculate_stake_fee(amount).unwrap();
+        let fees = calculate_stake_fee(amount, &FeeSchedule::default()).unwrap();
 
         // All fees should be 0 due to floor rounding
         assert_eq!(fees.stakers, 0);
@@ -185,9 +268,102 @@ mod tests {
     #[test]
     fn test_verify_fee_breakdown() {
         let amount = 1_000_000_000;
-        let fees = calculate_stake_fee(amount).unwrap();
+        let fees = calculate_stake_fee(amount, &FeeSchedule::default()).unwrap();
 
         // Should pass verification
         assert!(verify_fee_breakdown(&fees).is_ok());
     }
+
+    #[test]
+    fn test_custom_fee_schedule() {
+        let amount = 1_000_000_000;
+        let schedule = FeeSchedule {
+            stakers_bps: 300,
+            platform_bps: 100,
+            bonus_pool_bps: 50,
+            referral_bps: 25,
+            material_dart_bps: 25,
+        };
+        let fees = calculate_stake_fee(amount, &schedule).unwrap();
+
+        assert_eq!(fees.stakers, 30_000_000);
+        assert_eq!(fees.bonus_pool, 5_000_000);
+        assert_eq!(fees.total_fee, 50_000_000); // 5%
+        assert_eq!(fees.net_amount, 950_000_000);
+        assert!(validate_fee_schedule(&schedule).is_ok());
+    }
+
+    #[test]
+    fn test_unstake_fee_curve() {
+        const DAY: i64 = 86_400;
+        let curve = UnstakeFeeCurve {
+            enabled: true,
+            full_fee_seconds: 7 * DAY,
+            decay_seconds: 100 * DAY,
+            floor_bps: 200,
+        };
+        assert!(validate_unstake_fee_curve(&curve).is_ok());
+
+        // 10% during the first week, decaying linearly to the 2% floor
+        assert_eq!(calculate_unstake_fee_bps(1000, &curve, 0).unwrap(), 1000);
+        assert_eq!(
+            calculate_unstake_fee_bps(1000, &curve, 7 * DAY).unwrap(),
+            1000
+        );
+        assert_eq!(
+            calculate_unstake_fee_bps(1000, &curve, 57 * DAY).unwrap(),
+            600
+        );
+        assert_eq!(
+            calculate_unstake_fee_bps(1000, &curve, 365 * DAY).unwrap(),
+            200
+        );
+
+        // Components keep their share of the decayed total
+        let schedule = unstake_fee_schedule(&FeeSchedule::default(), &curve, 57 * DAY).unwrap();
+        assert_eq!(schedule.stakers_bps, 420);
+        assert_eq!(schedule.platform_bps, 60);
+        assert_eq!(schedule.referral_bps, 30);
+        assert_eq!(schedule.total_bps(), 600);
+
+        // Disabled curve: full schedule regardless of age
+        let disabled = UnstakeFeeCurve::default();
+        assert_eq!(
+            unstake_fee_schedule(&FeeSchedule::default(), &disabled, 365 * DAY).unwrap(),
+            FeeSchedule::default()
+        );
+
+        // Floor above the schedule total is capped at the total
+        let low_schedule = FeeSchedule {
+            stakers_bps: 100,
+            platform_bps: 0,
+            bonus_pool_bps: 0,
+            referral_bps: 0,
+            material_dart_bps: 0,
+        };
+        assert_eq!(
+            unstake_fee_schedule(&low_schedule, &curve, 365 * DAY).unwrap(),
+            low_schedule
+        );
+
+        let invalid = UnstakeFeeCurve {
+            decay_seconds: -1,
+            ..curve
+        };
+        assert!(validate_unstake_fee_curve(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_fee_schedule_cap() {
+        assert!(validate_fee_schedule(&FeeSchedule::default()).is_ok());
+
+        let schedule = FeeSchedule {
+            stakers_bps: MAX_TOTAL_FEE_BPS as u16,
+            platform_bps: 1,
+            bonus_pool_bps: 0,
+            referral_bps: 0,
+            material_dart_bps: 0,
+        };
+        assert!(validate_fee_schedule(&schedule).is_err());
+    }
 }
@@ -1,19 +1,29 @@
 use crate::constants::*;
 use crate::errors::StakingError;
+use crate::fees::calculate_stake_fee;
 use crate::math::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
 
 /// Update reward per share in staking pool
 ///
-/// Formula: reward_per_share += (fee_amount * REWARD_PRECISION) / total_staked
+/// Formula: reward_per_share += (fee_amount * REWARD_PRECISION) / total_weighted_shares
+///
+/// Liquid pools instead add the fee to the assets backing receipt shares,
+/// raising the share price.
 pub fn update_reward_per_share(pool: &mut StakingPool, fee_amount: u64) -> Result<()> {
     if pool.total_staked == 0 {
         // No stakers yet, fee goes to bonus/treasury but not tracked in reward_per_share
         return Ok(());
     }
 
-    let increase = calculate_reward_per_share_increase(fee_amount, pool.total_staked)?;
+    if pool.is_liquid() {
+        pool.total_staked = safe_add(pool.total_staked, fee_amount)?;
+        return Ok(());
+    }
+
+    let increase = calculate_reward_per_share_increase(fee_amount, pool.total_weighted_shares)?;
     pool.reward_per_share = safe_add_u128(pool.reward_per_share, increase)?;
 
     Ok(())
@@ -21,42 +31,427 @@ pub fn update_reward_per_share(pool: &mut StakingPool, fee_amount: u64) -> Resul
 
 /// Calculate pending rewards for a user
 ///
-/// Formula: pending = (staked_amount * reward_per_share / PRECISION) - reward_debt
+/// Formula: pending = accrued_rewards
+///     + (weighted_shares * reward_per_share / PRECISION) - reward_debt
 pub fn get_pending_rewards(user: &UserStakeState, pool: &StakingPool) -> Result<u64> {
-    calculate_pending_rewards(user.staked_amount, pool.reward_per_share, user.reward_debt)
+    safe_add(
+        user.accrued_rewards,
+        calculate_pending_rewards(
+            user.weighted_shares,
+            pool.reward_per_share,
+            user.reward_debt,
+        )?,
+    )
+}
+
+/// Carry a position's pending rewards into accrued_rewards
+///
+/// Used before the reward weight changes without a payout; reward_debt is
+/// reset from the current weight so nothing is counted twice.
+pub fn settle_rewards(user: &mut UserStakeState, pool: &StakingPool) -> Result<()> {
+    user.accrued_rewards = get_pending_rewards(user, pool)?;
+    user.reward_debt = calculate_reward_debt(user.weighted_shares, pool.reward_per_share)?;
+
+    Ok(())
+}
+
+/// Resolve the account a position's SOL rewards are paid to
+///
+/// Positions with a reward recipient must pass it; otherwise the owner is paid.
+/// A recipient drained below the rent-exempt minimum since it was set would
+/// fail the runtime's rent check, so `amount` then goes to the owner instead.
+pub fn reward_recipient_account<'info>(
+    user_stake: &UserStakeState,
+    user: &Signer<'info>,
+    reward_recipient: &Option<UncheckedAccount<'info>>,
+    amount: u64,
+) -> Result<AccountInfo<'info>> {
+    if user_stake.reward_recipient == Pubkey::default() {
+        return Ok(user.to_account_info());
+    }
+
+    let reward_recipient = reward_recipient
+        .as_ref()
+        .ok_or(StakingError::InvalidRewardRecipient)?;
+    require!(
+        reward_recipient.key() == user_stake.reward_recipient,
+        StakingError::InvalidRewardRecipient
+    );
+
+    let balance_after = safe_add(reward_recipient.lamports(), amount)?;
+    if balance_after < Rent::get()?.minimum_balance(0) {
+        msg!("Reward recipient below rent-exempt minimum; paying the owner");
+        return Ok(user.to_account_info());
+    }
+
+    Ok(reward_recipient.to_account_info())
+}
+
+/// Apply a stake's lockup tier to a position
+///
+/// A new tier locks the whole position until now + duration (never shortening
+/// an active lockup). While locked, the tier cannot be lowered; `None` top-ups
+/// join the active lockup.
+pub fn apply_lockup(
+    user_stake: &mut UserStakeState,
+    lockup_tier: LockupTier,
+    current_timestamp: i64,
+) -> Result<()> {
+    if lockup_tier == LockupTier::None {
+        return Ok(());
+    }
+
+    if user_stake.unlock_timestamp > current_timestamp {
+        require!(
+            lockup_tier.multiplier_bps() >= user_stake.lockup_tier.multiplier_bps(),
+            StakingError::LockupDowngrade
+        );
+    }
+
+    let unlock_timestamp = current_timestamp
+        .checked_add(lockup_tier.duration())
+        .ok_or(StakingError::MathOverflow)?;
+    user_stake.lockup_tier = lockup_tier;
+    user_stake.unlock_timestamp = user_stake.unlock_timestamp.max(unlock_timestamp);
+
+    Ok(())
+}
+
+/// Recompute a position's reward weight and the pool's total weight
+///
+/// Expired lockups drop back to 1x. Callers pay out or settle pending rewards
+/// first and reset reward_debt from the new weight afterwards.
+pub fn refresh_weighted_shares(
+    user_stake: &mut UserStakeState,
+    staking_pool: &mut StakingPool,
+    current_timestamp: i64,
+) -> Result<()> {
+    if user_stake.unlock_timestamp != 0 && user_stake.unlock_timestamp <= current_timestamp {
+        user_stake.lockup_tier = LockupTier::None;
+        user_stake.unlock_timestamp = 0;
+    }
+
+    let weighted_shares = calculate_weighted_shares(
+        user_stake.staked_amount,
+        user_stake.lockup_tier.multiplier_bps(),
+    )?;
+    staking_pool.total_weighted_shares = safe_add(
+        safe_sub(
+            staking_pool.total_weighted_shares,
+            user_stake.weighted_shares,
+        )?,
+        weighted_shares,
+    )?;
+    user_stake.weighted_shares = weighted_shares;
+
+    Ok(())
 }
 
 /// Extend bonus pool countdown
 ///
-/// Adds BONUS_EXTENSION_SECONDS (15 minutes) to the expiry timestamp
-pub fn extend_bonus_countdown(bonus_pool: &mut BonusPool) -> Result<()> {
-    let new_expiry = bonus_pool
+/// Adds BONUS_EXTENSION_SECONDS (15 minutes) to the expiry timestamp, or 15 minutes
+/// per whole SOL (whole token for token pools, up to max_extension_seconds) in
+/// proportional mode. The new expiry never exceeds max_countdown_seconds ahead of now.
+///
+/// Returns the effective extension in seconds
+pub fn extend_bonus_countdown(
+    bonus_pool: &mut BonusPool,
+    stake_amount: u64,
+    decimals: u8,
+    current_timestamp: i64,
+) -> Result<i64> {
+    let extension = if bonus_pool.proportional_extension {
+        let whole_sol = (stake_amount / scale_to_decimals(LAMPORTS_PER_SOL, decimals)?) as i64;
+        whole_sol
+            .checked_mul(BONUS_EXTENSION_SECONDS)
+            .ok_or(StakingError::MathOverflow)?
+            .min(bonus_pool.max_extension_seconds)
+    } else {
+        BONUS_EXTENSION_SECONDS
+    };
+
+    let mut new_expiry = bonus_pool
         .expiry_timestamp
-        .checked_add(BONUS_EXTENSION_SECONDS)
+        .checked_add(extension)
         .ok_or(StakingError::MathOverflow)?;
 
+    if bonus_pool.max_countdown_seconds > 0 {
+        let cap = current_timestamp
+            .checked_add(bonus_pool.max_countdown_seconds)
+            .ok_or(StakingError::MathOverflow)?;
+        // Never shorten an existing countdown
+        new_expiry = new_expiry.min(cap).max(bonus_pool.expiry_timestamp);
+    }
+
+    let effective = new_expiry - bonus_pool.expiry_timestamp;
     bonus_pool.expiry_timestamp = new_expiry;
-    Ok(())
+    Ok(effective)
 }
 
 /// Add investor to last-10 circular buffer
 ///
-/// Only called when stake amount >= 1 SOL
-pub fn add_to_last_ten(bonus_pool: &mut BonusPool, investor: Pubkey, amount: u64) -> Result<u8> {
+/// Only called when stake amount >= 1 SOL. With `dedupe_last_ten`, a repeat
+/// investor's existing slot is accumulated and moved to most-recent instead.
+///
+/// Returns: (position, updated_existing)
+pub fn add_to_last_ten(
+    bonus_pool: &mut BonusPool,
+    investor: Pubkey,
+    amount: u64,
+    timestamp: i64,
+) -> Result<(u8, bool)> {
+    if bonus_pool.dedupe_last_ten {
+        let mut entries = ordered_last_ten(bonus_pool);
+        if let Some(index) = entries.iter().position(|entry| entry.investor == investor) {
+            let existing = entries.remove(index);
+
+            // Amounts from a previous round don't count when only the current round is paid
+            let carried = if bonus_pool.last_ten_policy == LastTenPolicy::CurrentRoundOnly
+                && existing.timestamp < bonus_pool.round_start_timestamp
+            {
+                0
+            } else {
+                existing.amount
+            };
+
+            entries.push(LastTenInvestor {
+                investor,
+                amount: safe_add(carried, amount)?,
+                timestamp,
+            });
+
+            // Rewrite buffer oldest-first so the refreshed entry is most recent
+            clear_last_ten(bonus_pool);
+            for (slot, entry) in bonus_pool.last_ten_investors.iter_mut().zip(entries.iter()) {
+                *slot = *entry;
+            }
+            let count = entries.len() as u8;
+            bonus_pool.investor_count = count;
+            bonus_pool.current_position = count % bonus_pool.config.max_last_ten;
+
+            return Ok((count - 1, true));
+        }
+    }
+
     // Add to circular buffer
     let position = bonus_pool.current_position as usize;
-    bonus_pool.last_ten_investors[position] = LastTenInvestor { investor, amount };
+    bonus_pool.last_ten_investors[position] = LastTenInvestor {
+        investor,
+        amount,
+        timestamp,
+    };
 
-    // Update position (0-9 circular)
-    let new_position = (bonus_pool.current_position + 1) % (MAX_LAST_TEN_INVESTORS as u8);
+    // Update position (circular over the active list size)
+    let new_position = (bonus_pool.current_position + 1) % bonus_pool.config.max_last_ten;
     bonus_pool.current_position = new_position;
 
     // Increment count if not yet filled
-    if (bonus_pool.investor_count as usize) < MAX_LAST_TEN_INVESTORS {
+    if bonus_pool.investor_count < bonus_pool.config.max_last_ten {
         bonus_pool.investor_count += 1;
     }
 
-    Ok(position as u8)
+    Ok((position as u8, false))
+}
+
+/// Last-10 entries ordered oldest to most recent
+pub fn ordered_last_ten(bonus_pool: &BonusPool) -> Vec<LastTenInvestor> {
+    let count = bonus_pool.investor_count as usize;
+    let capacity = bonus_pool.config.max_last_ten as usize;
+
+    // Until the buffer wraps, entries start at slot 0
+    let oldest = if count < capacity {
+        0
+    } else {
+        bonus_pool.current_position as usize
+    };
+
+    (0..count)
+        .map(|i| bonus_pool.last_ten_investors[(oldest + i) % capacity])
+        .collect()
+}
+
+/// Change the active last-10 list size, keeping the most recent entries
+pub fn resize_last_ten(bonus_pool: &mut BonusPool, max_last_ten: u8) {
+    let entries = ordered_last_ten(bonus_pool);
+    let keep = entries.len().min(max_last_ten as usize);
+
+    clear_last_ten(bonus_pool);
+    bonus_pool.config.max_last_ten = max_last_ten;
+    for (slot, entry) in bonus_pool
+        .last_ten_investors
+        .iter_mut()
+        .zip(entries[entries.len() - keep..].iter())
+    {
+        *slot = *entry;
+    }
+    bonus_pool.investor_count = keep as u8;
+    bonus_pool.current_position = keep as u8 % max_last_ten;
+}
+
+/// Last-10 entries eligible for the current round under the pool's policy
+///
+/// Entries are returned oldest to most recent
+pub fn eligible_last_ten(bonus_pool: &BonusPool) -> Vec<LastTenInvestor> {
+    ordered_last_ten(bonus_pool)
+        .into_iter()
+        .filter(|entry| match bonus_pool.last_ten_policy {
+            LastTenPolicy::CurrentRoundOnly => entry.timestamp >= bonus_pool.round_start_timestamp,
+            LastTenPolicy::Persist | LastTenPolicy::ClearOnDistribution => true,
+        })
+        .collect()
+}
+
+/// Net stake a gross stake of `threshold` results in (last-10 entries hold net amounts)
+pub fn last_ten_net_threshold(threshold: u64, fee_schedule: &FeeSchedule) -> Result<u64> {
+    Ok(calculate_stake_fee(threshold, fee_schedule)?.net_amount)
+}
+
+/// Re-validate last-10 entries against investors' current stake positions
+///
+/// Each investor's entries are capped, most recent first, by their current
+/// staked_amount; entries whose remaining weight falls below `net_threshold`
+/// are dropped. Entries must be ordered oldest to most recent.
+pub fn revalidate_last_ten(
+    entries: &[LastTenInvestor],
+    investor_stakes: &[UserStakeState],
+    net_threshold: u64,
+) -> Result<Vec<LastTenInvestor>> {
+    let mut remaining: Vec<(Pubkey, u64)> = Vec::with_capacity(entries.len());
+    let mut validated = Vec::with_capacity(entries.len());
+
+    for entry in entries.iter().rev() {
+        let index = match remaining.iter().position(|(key, _)| *key == entry.investor) {
+            Some(index) => index,
+            None => {
+                let user_stake = investor_stakes
+                    .iter()
+                    .find(|stake| stake.user == entry.investor)
+                    .ok_or(StakingError::MissingInvestorStake)?;
+                remaining.push((entry.investor, user_stake.staked_amount));
+                remaining.len() - 1
+            }
+        };
+        let budget = &mut remaining[index].1;
+
+        let weight = entry.amount.min(*budget);
+        *budget -= weight;
+
+        if weight >= net_threshold {
+            validated.push(LastTenInvestor {
+                amount: weight,
+                ..*entry
+            });
+        }
+    }
+
+    validated.reverse();
+    Ok(validated)
+}
+
+/// Calculate each last-10 investor's pro-rata share of the last-10 slice
+///
+/// Returns: (winners, winner_count, total_paid)
+pub fn calculate_last_ten_shares(
+    entries: &[LastTenInvestor],
+    to_last_ten: u64,
+) -> Result<([BonusRoundWinner; MAX_LAST_TEN_INVESTORS], u8, u64)> {
+    let mut winners = [BonusRoundWinner::default(); MAX_LAST_TEN_INVESTORS];
+
+    // Calculate total invested by last-10
+    let mut total_invested: u64 = 0;
+    for entry in entries {
+        total_invested = safe_add(total_invested, entry.amount)?;
+    }
+
+    let mut total_paid: u64 = 0;
+    for (entry, winner) in entries.iter().zip(winners.iter_mut()) {
+        winner.investor = entry.investor;
+        winner.amount = entry.amount;
+        winner.share = calculate_pro_rata_share(entry.amount, total_invested, to_last_ten)?;
+        total_paid = safe_add(total_paid, winner.share)?;
+    }
+
+    Ok((winners, entries.len() as u8, total_paid))
+}
+
+/// Mark an investor's unclaimed last-10 shares of a round as claimed
+///
+/// Returns the total owed (an investor may hold several entries).
+pub fn take_last_ten_shares(winners: &mut [BonusRoundWinner], investor: &Pubkey) -> Result<u64> {
+    let mut amount: u64 = 0;
+    for winner in winners.iter_mut() {
+        if winner.investor == *investor && winner.share > 0 && !winner.claimed {
+            amount = safe_add(amount, winner.share)?;
+            winner.claimed = true;
+        }
+    }
+    Ok(amount)
+}
+
+/// Whether anti-sniping rules require investor stake accounts at distribution
+pub fn anti_sniping_enabled(bonus_pool: &BonusPool) -> bool {
+    bonus_pool.min_hold_seconds > 0 || bonus_pool.forfeit_on_unstake
+}
+
+/// Check whether a last-10 entry loses its share under anti-sniping rules
+///
+/// An entry is forfeited if it was held for less than min_hold_seconds, or
+/// (with forfeit_on_unstake) if the investor unstaked after entering the list.
+pub fn is_last_ten_share_forfeited(
+    bonus_pool: &BonusPool,
+    entry: &LastTenInvestor,
+    user_stake: &UserStakeState,
+    current_timestamp: i64,
+) -> bool {
+    let held_for = current_timestamp.saturating_sub(entry.timestamp);
+    if held_for < bonus_pool.min_hold_seconds {
+        return true;
+    }
+
+    bonus_pool.forfeit_on_unstake && user_stake.last_unstake_timestamp >= entry.timestamp
+}
+
+/// Load investor stake positions passed as remaining accounts
+///
+/// Each account must be a UserStakeState PDA of the given staking pool.
+pub fn load_investor_stakes(
+    remaining_accounts: &[AccountInfo],
+    staking_pool: &Pubkey,
+) -> Result<Vec<UserStakeState>> {
+    let mut stakes = Vec::with_capacity(remaining_accounts.len());
+
+    for account in remaining_accounts {
+        require!(
+            account.owner == &crate::ID,
+            StakingError::InvalidAccountOwner
+        );
+
+        let data = account.try_borrow_data()?;
+        let user_stake = UserStakeState::try_deserialize(&mut &data[..])?;
+
+        let expected = Pubkey::create_program_address(
+            &[
+                seeds::USER_STAKE,
+                user_stake.user.as_ref(),
+                staking_pool.as_ref(),
+                &[user_stake.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| StakingError::InvalidPDA)?;
+        require!(account.key() == expected, StakingError::InvalidPDA);
+
+        stakes.push(user_stake);
+    }
+
+    Ok(stakes)
+}
+
+/// Clear the last-10 circular buffer
+pub fn clear_last_ten(bonus_pool: &mut BonusPool) {
+    bonus_pool.last_ten_investors = [LastTenInvestor::default(); MAX_LAST_TEN_INVESTORS];
+    bonus_pool.current_position = 0;
+    bonus_pool.investor_count = 0;
 }
 
 /// Check if bonus pool countdown has expired
@@ -64,24 +459,130 @@ pub fn check_bonus_expiry(bonus_pool: &BonusPool, current_timestamp: i64) -> boo
     current_timestamp >= bonus_pool.expiry_timestamp
 }
 
-/// Check if 6-hour inactivity trigger is met
+/// Check if inactivity trigger (6 hours by default) is met
 pub fn check_inactivity_trigger(bonus_pool: &BonusPool, current_timestamp: i64) -> bool {
     let time_since_last_investment = current_timestamp - bonus_pool.last_investment_timestamp;
-    time_since_last_investment >= BONUS_INACTIVITY_THRESHOLD
+    time_since_last_investment >= bonus_pool.config.inactivity_threshold
+}
+
+/// Deadline that ended the current round, if any
+///
+/// When both deadlines have passed, the one reached first wins. Deadlines are
+/// frozen once the round expires, so the result is stable until distribution.
+pub fn bonus_expiry_trigger(
+    bonus_pool: &BonusPool,
+    current_timestamp: i64,
+) -> Option<BonusTrigger> {
+    let countdown_expired = check_bonus_expiry(bonus_pool, current_timestamp);
+    let inactivity_trigger = check_inactivity_trigger(bonus_pool, current_timestamp);
+
+    match (countdown_expired, inactivity_trigger) {
+        (false, false) => None,
+        (true, false) => Some(BonusTrigger::Countdown),
+        (false, true) => Some(BonusTrigger::Inactivity),
+        (true, true) => {
+            let inactivity_deadline = bonus_pool
+                .last_investment_timestamp
+                .saturating_add(bonus_pool.config.inactivity_threshold);
+            if bonus_pool.expiry_timestamp <= inactivity_deadline {
+                Some(BonusTrigger::Countdown)
+            } else {
+                Some(BonusTrigger::Inactivity)
+            }
+        }
+    }
 }
 
-/// Calculate bonus pool distribution amounts
+/// Move an Active round to Expired once a deadline has passed
+///
+/// Returns the current round state
+pub fn refresh_bonus_round_state(
+    bonus_pool: &mut BonusPool,
+    current_timestamp: i64,
+) -> BonusRoundState {
+    if bonus_pool.round_state == BonusRoundState::Active
+        && bonus_expiry_trigger(bonus_pool, current_timestamp).is_some()
+    {
+        bonus_pool.round_state = BonusRoundState::Expired;
+    }
+
+    bonus_pool.round_state
+}
+
+/// Start a new round after a distribution (Distributed → Active)
+pub fn start_bonus_round(bonus_pool: &mut BonusPool, current_timestamp: i64) -> Result<()> {
+    require!(
+        bonus_pool.round_state == BonusRoundState::Distributed,
+        StakingError::InvalidBonusRoundState
+    );
+
+    bonus_pool.round_state = BonusRoundState::Active;
+    bonus_pool.round_start_timestamp = current_timestamp;
+    bonus_pool.last_investment_timestamp = current_timestamp;
+    bonus_pool.expiry_timestamp = current_timestamp
+        .checked_add(bonus_pool.config.initial_countdown)
+        .ok_or(StakingError::MathOverflow)?;
+
+    Ok(())
+}
+
+/// Validate bonus configuration
+///
+/// Ratios must sum to 10000 BPS, durations and thresholds must be positive
+/// and the last-10 size must fit the buffer.
+pub fn validate_bonus_config(config: &BonusConfig) -> Result<()> {
+    let total_bps =
+        config.last_ten_bps as u64 + config.stakers_bps as u64 + config.carry_forward_bps as u64;
+    require!(
+        total_bps == BPS_DENOMINATOR,
+        StakingError::InvalidBonusConfig
+    );
+    require!(
+        config.initial_countdown > 0 && config.inactivity_threshold > 0,
+        StakingError::InvalidBonusConfig
+    );
+    require!(
+        config.last_ten_threshold > 0,
+        StakingError::InvalidBonusConfig
+    );
+    require!(
+        config.max_last_ten > 0 && config.max_last_ten as usize <= MAX_LAST_TEN_INVESTORS,
+        StakingError::InvalidBonusConfig
+    );
+    require!(
+        config.jackpot_bps as u64 <= BPS_DENOMINATOR,
+        StakingError::InvalidBonusConfig
+    );
+    if config.mode == BonusMode::LastInvestorJackpot {
+        require!(config.jackpot_bps > 0, StakingError::InvalidBonusConfig);
+    }
+
+    Ok(())
+}
+
+/// Calculate the last-investor jackpot
+///
+/// Returns: (jackpot, remaining_bonus)
+pub fn calculate_jackpot(total_bonus: u64, config: &BonusConfig) -> Result<(u64, u64)> {
+    let jackpot = calculate_bps_percentage(total_bonus, config.jackpot_bps as u64)?;
+    Ok((jackpot, safe_sub(total_bonus, jackpot)?))
+}
+
+/// Calculate bonus pool distribution amounts (40/40/20 by default)
 ///
 /// Returns: (to_last_ten, to_all_stakers, carry_forward)
-pub fn calculate_bonus_distribution(total_bonus: u64) -> Result<(u64, u64, u64)> {
-    // 40% to last 10 investors
-    let to_last_ten = calculate_bps_percentage(total_bonus, 4000)?; // 40%
+pub fn calculate_bonus_distribution(
+    total_bonus: u64,
+    config: &BonusConfig,
+) -> Result<(u64, u64, u64)> {
+    // Share to last 10 investors
+    let to_last_ten = calculate_bps_percentage(total_bonus, config.last_ten_bps as u64)?;
 
-    // 40% to all stakers
-    let to_all_stakers = calculate_bps_percentage(total_bonus, 4000)?; // 40%
+    // Share to all stakers
+    let to_all_stakers = calculate_bps_percentage(total_bonus, config.stakers_bps as u64)?;
 
-    // 20% carry forward
-    let carry_forward = calculate_bps_percentage(total_bonus, 2000)?; // 20%
+    // Carry forward
+    let carry_forward = calculate_bps_percentage(total_bonus, config.carry_forward_bps as u64)?;
 
     // Verify sum (accounting for rounding)
     let total_distributed = to_last_ten
@@ -101,15 +602,82 @@ pub fn calculate_bonus_distribution(total_bonus: u64) -> Result<(u64, u64, u64)>
 
 /// Calculate referral pool distribution amounts
 ///
-/// Returns: (to_stakers, carry_forward)
-pub fn calculate_referral_distribution(total_pool: u64) -> Result<(u64, u64)> {
+/// `referrer_share_bps` is carved out of the carried-forward half (0 = stakers only)
+///
+/// Returns: (to_stakers, to_referrers, carry_forward)
+pub fn calculate_referral_distribution(
+    total_pool: u64,
+    referrer_share_bps: u16,
+) -> Result<(u64, u64, u64)> {
+    require!(
+        referrer_share_bps <= MAX_REFERRER_SHARE_BPS,
+        StakingError::InvalidReferralConfig
+    );
+
     // 50% to stakers
-    let to_stakers = calculate_bps_percentage(total_pool, 5000)?; // 50%
+    let to_stakers = calculate_bps_percentage(total_pool, REFERRAL_STAKERS_BPS)?; // 50%
+
+    // Referrer share (pro-rata to referred volume)
+    let to_referrers = calculate_bps_percentage(total_pool, referrer_share_bps as u64)?;
+
+    // Remainder carries forward
+    let carry_forward = safe_sub(safe_sub(total_pool, to_stakers)?, to_referrers)?;
+
+    Ok((to_stakers, to_referrers, carry_forward))
+}
+
+/// Settle a referrer's volume from the last closed referral period
+///
+/// Volume is settled against the previous period's allocation only; volume from
+/// older periods was not settled in time and its allocation has been swept back
+/// into the pool.
+///
+/// Returns the amount added to the referrer's claimable balance
+pub fn settle_referrer_rewards(
+    referrer: &mut ReferrerAccount,
+    referral_pool: &mut ReferralPool,
+) -> Result<u64> {
+    if referrer.volume_epoch == referral_pool.current_epoch {
+        return Ok(0);
+    }
+
+    let mut settled = 0;
+    if referrer.epoch_volume > 0
+        && referrer.volume_epoch.checked_add(1) == Some(referral_pool.current_epoch)
+    {
+        settled = calculate_pro_rata_share(
+            referrer.epoch_volume,
+            referral_pool.last_epoch_referred_volume,
+            referral_pool.last_epoch_referrer_allocation,
+        )?;
+
+        referrer.claimable = safe_add(referrer.claimable, settled)?;
+        referral_pool.last_epoch_referrer_settled =
+            safe_add(referral_pool.last_epoch_referrer_settled, settled)?;
+        referral_pool.referrer_claimable = safe_add(referral_pool.referrer_claimable, settled)?;
+        // Only settled amounts count as distributed; the rest is swept back
+        referral_pool.total_distributed = safe_add(referral_pool.total_distributed, settled)?;
+    }
+
+    referrer.volume_epoch = referral_pool.current_epoch;
+    referrer.epoch_volume = 0;
+
+    Ok(settled)
+}
+
+/// Record gross stake volume brought in by a registered referrer
+pub fn record_referred_volume(
+    referrer: &mut ReferrerAccount,
+    referral_pool: &mut ReferralPool,
+    amount: u64,
+) -> Result<()> {
+    settle_referrer_rewards(referrer, referral_pool)?;
 
-    // 50% carry forward
-    let carry_forward = total_pool.saturating_sub(to_stakers);
+    referrer.epoch_volume = safe_add(referrer.epoch_volume, amount)?;
+    referrer.total_volume = safe_add(referrer.total_volume, amount)?;
+    referral_pool.epoch_referred_volume = safe_add(referral_pool.epoch_referred_volume, amount)?;
 
-    Ok((to_stakers, carry_forward))
+    Ok(())
 }
 
 /// Check if referral pool distribution period has ended
@@ -126,6 +694,212 @@ fn calculate_bps_percentage(amount: u64, bps: u64) -> Result<u64> {
     safe_div(numerator, BPS_DENOMINATOR)
 }
 
+/// Validate a pool's delegation target
+///
+/// At least BPS_DENOMINATOR - MAX_DELEGATION_TARGET_BPS of total_staked stays liquid
+pub fn validate_delegation_target(delegation_target_bps: u16) -> Result<()> {
+    require!(
+        delegation_target_bps <= MAX_DELEGATION_TARGET_BPS,
+        StakingError::InvalidDelegationTarget
+    );
+
+    Ok(())
+}
+
+/// Lamports that may still be delegated without exceeding the delegation target
+///
+/// Formula: capacity = total_staked * target_bps / 10000 - total_delegated (floored at 0)
+pub fn calculate_delegation_capacity(
+    total_staked: u64,
+    total_delegated: u64,
+    delegation_target_bps: u16,
+) -> Result<u64> {
+    let target = calculate_bps_percentage(total_staked, delegation_target_bps as u64)?;
+    Ok(target.saturating_sub(total_delegated))
+}
+
+/// Undelegated lamports of a SOL pool account above its rent-exempt minimum
+///
+/// Delegated lamports only return after deactivation and withdrawal.
+pub fn pool_liquidity(staking_pool: &AccountInfo) -> Result<u64> {
+    let rent_reserve = Rent::get()?.minimum_balance(staking_pool.data_len());
+    Ok(staking_pool.lamports().saturating_sub(rent_reserve))
+}
+
+/// Require `amount` lamports of pool liquidity not reserved for queued withdrawals
+pub fn require_pool_liquidity(staking_pool: &Account<StakingPool>, amount: u64) -> Result<()> {
+    let available = pool_liquidity(&staking_pool.to_account_info())?
+        .saturating_sub(staking_pool.total_pending_unstake);
+    require!(available >= amount, StakingError::InsufficientLiquidity);
+
+    Ok(())
+}
+
+/// Validate a pool's unstake cooldown
+pub fn validate_unstake_cooldown(cooldown_seconds: i64) -> Result<()> {
+    require!(
+        (0..=MAX_UNSTAKE_COOLDOWN).contains(&cooldown_seconds),
+        StakingError::InvalidUnstakeCooldown
+    );
+
+    Ok(())
+}
+
+/// Whether adding `amount` to `current` stays within `cap` (0 = no cap)
+pub fn within_cap(current: u64, amount: u64, cap: u64) -> Result<bool> {
+    Ok(cap == 0 || safe_add(current, amount)? <= cap)
+}
+
+/// Validate a set of pause flags (non-empty, known operations only)
+pub fn validate_pause_flags(flags: u8) -> Result<()> {
+    require!(
+        flags != 0 && flags & !pause_flags::ALL == 0,
+        StakingError::InvalidPauseFlags
+    );
+
+    Ok(())
+}
+
+/// Pause or unpause operations on behalf of `caller`
+///
+/// The authority may pause and unpause; the guardian may only pause.
+pub fn apply_pause_flags(
+    global_config: &mut GlobalConfig,
+    caller: &Pubkey,
+    flags: u8,
+    paused: bool,
+) -> Result<()> {
+    validate_pause_flags(flags)?;
+
+    let is_authority = *caller == global_config.authority;
+    require!(
+        is_authority || global_config.is_guardian(caller),
+        StakingError::Unauthorized
+    );
+    require!(paused || is_authority, StakingError::GuardianCannotUnpause);
+
+    if paused {
+        global_config.pause_flags |= flags;
+    } else {
+        global_config.pause_flags &= !flags;
+    }
+
+    Ok(())
+}
+
+/// Merkle leaf of an allowlisted wallet
+pub fn allowlist_leaf(user: &Pubkey) -> [u8; 32] {
+    hashv(&[&[MERKLE_LEAF_PREFIX], user.as_ref()]).to_bytes()
+}
+
+/// Verify a Merkle proof of `leaf` against `root`
+///
+/// Sibling pairs are hashed in sorted order, so proofs carry no path bits.
+pub fn verify_merkle_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        hashv(&[&[MERKLE_NODE_PREFIX], left.as_ref(), right.as_ref()]).to_bytes()
+    });
+
+    computed == *root
+}
+
+/// Initialize a freshly created staking pool
+pub fn init_staking_pool(
+    staking_pool: &mut StakingPool,
+    config: Pubkey,
+    pool_id: u64,
+    fee_schedule: FeeSchedule,
+    bump: u8,
+    current_timestamp: i64,
+) {
+    staking_pool.config = config;
+    staking_pool.total_staked = 0;
+    staking_pool.reward_per_share = 0;
+    staking_pool.last_update_timestamp = current_timestamp;
+    staking_pool.bump = bump;
+    staking_pool.pool_id = pool_id;
+    staking_pool.fee_schedule = fee_schedule;
+    staking_pool.mint = Pubkey::default();
+    staking_pool.vault = Pubkey::default();
+    staking_pool.token_program = Pubkey::default();
+    staking_pool.receipt_mint = Pubkey::default();
+    staking_pool.total_shares = 0;
+    staking_pool.unstake_fee_curve = UnstakeFeeCurve::default();
+    staking_pool.total_weighted_shares = 0;
+    staking_pool.unstake_cooldown_seconds = 0;
+    staking_pool.total_pending_unstake = 0;
+    staking_pool.delegation_target_bps = 0;
+    staking_pool.total_delegated = 0;
+    staking_pool.stake_account_count = 0;
+    staking_pool.max_total_staked = 0;
+    staking_pool.max_stake_per_user = 0;
+    staking_pool.allowlist_enabled = false;
+    staking_pool.decimals = SOL_DECIMALS;
+}
+
+/// Initialize a pool's bonus pool with default config (12-hour countdown)
+pub fn init_bonus_pool(
+    bonus_pool: &mut BonusPool,
+    staking_pool: Pubkey,
+    bump: u8,
+    current_timestamp: i64,
+) -> Result<()> {
+    bonus_pool.staking_pool = staking_pool;
+    bonus_pool.balance = 0;
+    bonus_pool.expiry_timestamp = current_timestamp
+        .checked_add(BONUS_INITIAL_COUNTDOWN)
+        .ok_or(StakingError::MathOverflow)?;
+    bonus_pool.last_investment_timestamp = current_timestamp;
+    bonus_pool.last_ten_investors = [LastTenInvestor::default(); MAX_LAST_TEN_INVESTORS];
+    bonus_pool.current_position = 0;
+    bonus_pool.investor_count = 0;
+    bonus_pool.current_round = 0;
+    bonus_pool.round_start_timestamp = current_timestamp;
+    bonus_pool.last_ten_policy = LastTenPolicy::Persist;
+    bonus_pool.dedupe_last_ten = false;
+    bonus_pool.max_countdown_seconds = BONUS_MAX_COUNTDOWN;
+    bonus_pool.proportional_extension = false;
+    bonus_pool.max_extension_seconds = BONUS_MAX_EXTENSION_SECONDS;
+    bonus_pool.min_hold_seconds = 0;
+    bonus_pool.forfeit_on_unstake = false;
+    bonus_pool.config = BonusConfig::default();
+    bonus_pool.round_state = BonusRoundState::Active;
+    bonus_pool.bump = bump;
+
+    Ok(())
+}
+
+/// Initialize a pool's referral pool with a 30-day distribution period
+pub fn init_referral_pool(
+    referral_pool: &mut ReferralPool,
+    staking_pool: Pubkey,
+    bump: u8,
+    current_timestamp: i64,
+) -> Result<()> {
+    referral_pool.staking_pool = staking_pool;
+    referral_pool.balance = 0;
+    referral_pool.next_distribution_timestamp = current_timestamp
+        .checked_add(REFERRAL_DISTRIBUTION_PERIOD)
+        .ok_or(StakingError::MathOverflow)?;
+    referral_pool.last_distribution_timestamp = current_timestamp;
+    referral_pool.total_distributed = 0;
+    referral_pool.referrer_share_bps = 0;
+    referral_pool.current_epoch = 0;
+    referral_pool.epoch_referred_volume = 0;
+    referral_pool.last_epoch_referrer_allocation = 0;
+    referral_pool.last_epoch_referred_volume = 0;
+    referral_pool.last_epoch_referrer_settled = 0;
+    referral_pool.referrer_claimable = 0;
+    referral_pool.bump = bump;
+
+    Ok(())
+}
+
 /// Get current timestamp from Clock sysvar
 pub fn get_current_timestamp() -> Result<i64> {
     let clock = Clock::get()?;
@@ -140,7 +914,8 @@ mod tests {
     fn test_bonus_distribution_40_40_20() {
         let total = 1_000_000_000; // 1 SOL
 
-        let (last_ten, stakers, carry) = calculate_bonus_distribution(total).unwrap();
+        let (last_ten, stakers, carry) =
+            calculate_bonus_distribution(total, &BonusConfig::default()).unwrap();
 
         // 40% = 400_000_000
         // 40% = 400_000_000
@@ -153,16 +928,850 @@ mod tests {
         assert_eq!(last_ten + stakers + carry, total);
     }
 
+    #[test]
+    fn test_bonus_distribution_custom_config() {
+        let config = BonusConfig {
+            last_ten_bps: 5000,
+            stakers_bps: 3000,
+            carry_forward_bps: 2000,
+            ..BonusConfig::default()
+        };
+        assert!(validate_bonus_config(&config).is_ok());
+
+        let (last_ten, stakers, carry) =
+            calculate_bonus_distribution(1_000_000_000, &config).unwrap();
+        assert_eq!(last_ten, 500_000_000);
+        assert_eq!(stakers, 300_000_000);
+        assert_eq!(carry, 200_000_000);
+    }
+
+    #[test]
+    fn test_jackpot_then_split() {
+        let config = BonusConfig {
+            mode: BonusMode::LastInvestorJackpot,
+            jackpot_bps: 5000,
+            ..BonusConfig::default()
+        };
+        assert!(validate_bonus_config(&config).is_ok());
+
+        let (jackpot, rest) = calculate_jackpot(1_000_000_000, &config).unwrap();
+        assert_eq!(jackpot, 500_000_000);
+        assert_eq!(rest, 500_000_000);
+
+        // The rest is split 40/40/20 as today
+        let (last_ten, stakers, carry) = calculate_bonus_distribution(rest, &config).unwrap();
+        assert_eq!(last_ten, 200_000_000);
+        assert_eq!(stakers, 200_000_000);
+        assert_eq!(carry, 100_000_000);
+
+        // Jackpot mode needs a jackpot
+        let config = BonusConfig {
+            jackpot_bps: 0,
+            ..config
+        };
+        assert!(validate_bonus_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_bonus_config_validation() {
+        assert!(validate_bonus_config(&BonusConfig::default()).is_ok());
+
+        // Ratios must sum to 10000 BPS
+        let config = BonusConfig {
+            carry_forward_bps: 1000,
+            ..BonusConfig::default()
+        };
+        assert!(validate_bonus_config(&config).is_err());
+
+        // Durations must be positive
+        let config = BonusConfig {
+            initial_countdown: 0,
+            ..BonusConfig::default()
+        };
+        assert!(validate_bonus_config(&config).is_err());
+        let config = BonusConfig {
+            inactivity_threshold: -1,
+            ..BonusConfig::default()
+        };
+        assert!(validate_bonus_config(&config).is_err());
+
+        // Last-10 size must fit the buffer
+        let config = BonusConfig {
+            max_last_ten: (MAX_LAST_TEN_INVESTORS + 1) as u8,
+            ..BonusConfig::default()
+        };
+        assert!(validate_bonus_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_resize_last_ten_keeps_most_recent() {
+        let mut bonus_pool = test_bonus_pool();
+        let investors: Vec<Pubkey> = (0..MAX_LAST_TEN_INVESTORS + 3)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        for (i, investor) in investors.iter().enumerate() {
+            add_to_last_ten(&mut bonus_pool, *investor, 1_000_000_000, i as i64).unwrap();
+        }
+
+        resize_last_ten(&mut bonus_pool, 3);
+        let entries = ordered_last_ten(&bonus_pool);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].investor, investors[MAX_LAST_TEN_INVESTORS]);
+        assert_eq!(entries[2].investor, investors[MAX_LAST_TEN_INVESTORS + 2]);
+
+        // New entries rotate within the smaller list
+        let newest = Pubkey::new_unique();
+        add_to_last_ten(&mut bonus_pool, newest, 1_000_000_000, 100).unwrap();
+        let entries = ordered_last_ten(&bonus_pool);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].investor, investors[MAX_LAST_TEN_INVESTORS + 1]);
+        assert_eq!(entries[2].investor, newest);
+    }
+
+    fn test_bonus_pool() -> BonusPool {
+        BonusPool {
+            staking_pool: Pubkey::default(),
+            balance: 0,
+            expiry_timestamp: 0,
+            last_investment_timestamp: 0,
+            last_ten_investors: [LastTenInvestor::default(); MAX_LAST_TEN_INVESTORS],
+            current_position: 0,
+            investor_count: 0,
+            total_participants: 0,
+            bump: 0,
+            current_round: 0,
+            round_start_timestamp: 0,
+            last_ten_policy: LastTenPolicy::Persist,
+            dedupe_last_ten: false,
+            max_countdown_seconds: 0,
+            proportional_extension: false,
+            max_extension_seconds: BONUS_MAX_EXTENSION_SECONDS,
+            min_hold_seconds: 0,
+            forfeit_on_unstake: false,
+            config: BonusConfig::default(),
+            round_state: BonusRoundState::Active,
+        }
+    }
+
+    #[test]
+    fn test_bonus_round_state_machine() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.expiry_timestamp = BONUS_INITIAL_COUNTDOWN;
+
+        // Mid-countdown with recent activity: still active
+        assert_eq!(
+            refresh_bonus_round_state(&mut bonus_pool, 3_600),
+            BonusRoundState::Active
+        );
+        assert!(start_bonus_round(&mut bonus_pool, 3_600).is_err());
+
+        // 6 hours without a qualifying stake: expired by inactivity
+        assert_eq!(
+            refresh_bonus_round_state(&mut bonus_pool, BONUS_INACTIVITY_THRESHOLD),
+            BonusRoundState::Expired
+        );
+        assert_eq!(
+            bonus_expiry_trigger(&bonus_pool, BONUS_INACTIVITY_THRESHOLD),
+            Some(BonusTrigger::Inactivity)
+        );
+
+        // Trigger stays inactivity even once the countdown has also passed
+        assert_eq!(
+            bonus_expiry_trigger(&bonus_pool, BONUS_INITIAL_COUNTDOWN + 1),
+            Some(BonusTrigger::Inactivity)
+        );
+
+        // After distribution nothing can expire until a new round starts
+        bonus_pool.round_state = BonusRoundState::Distributed;
+        assert_eq!(
+            refresh_bonus_round_state(&mut bonus_pool, 10 * BONUS_INITIAL_COUNTDOWN),
+            BonusRoundState::Distributed
+        );
+
+        // Next qualifying stake starts a fresh countdown
+        let now = 10 * BONUS_INITIAL_COUNTDOWN;
+        start_bonus_round(&mut bonus_pool, now).unwrap();
+        assert_eq!(bonus_pool.round_state, BonusRoundState::Active);
+        assert_eq!(bonus_pool.round_start_timestamp, now);
+        assert_eq!(bonus_pool.expiry_timestamp, now + BONUS_INITIAL_COUNTDOWN);
+        assert_eq!(
+            refresh_bonus_round_state(&mut bonus_pool, now + 1),
+            BonusRoundState::Active
+        );
+    }
+
+    #[test]
+    fn test_bonus_countdown_trigger() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.expiry_timestamp = 3_600;
+        bonus_pool.last_investment_timestamp = 0;
+
+        assert_eq!(bonus_expiry_trigger(&bonus_pool, 3_599), None);
+        assert_eq!(
+            bonus_expiry_trigger(&bonus_pool, 3_600),
+            Some(BonusTrigger::Countdown)
+        );
+    }
+
+    fn test_user_stake(user: Pubkey, last_unstake_timestamp: i64) -> UserStakeState {
+        UserStakeState {
+            user,
+            pool: Pubkey::default(),
+            staked_amount: 1_000_000_000,
+            reward_debt: 0,
+            stake_timestamp: 0,
+            last_claim_timestamp: 0,
+            referrer: None,
+            bump: 0,
+            last_unstake_timestamp,
+            lockup_tier: LockupTier::None,
+            unlock_timestamp: 0,
+            weighted_shares: 1_000_000_000,
+            pending_unstake: 0,
+            unstake_ready_timestamp: 0,
+            reward_recipient: Pubkey::default(),
+            accrued_rewards: 0,
+        }
+    }
+
+    #[test]
+    fn test_last_ten_revalidated_against_current_stake() {
+        let mut bonus_pool = test_bonus_pool();
+        let holder = Pubkey::new_unique();
+        let leaver = Pubkey::new_unique();
+        let splitter = Pubkey::new_unique();
+        let net_threshold =
+            last_ten_net_threshold(BONUS_LAST_TEN_THRESHOLD, &FeeSchedule::default()).unwrap();
+        assert_eq!(net_threshold, 900_000_000);
+
+        add_to_last_ten(&mut bonus_pool, holder, 1_800_000_000, 1).unwrap();
+        add_to_last_ten(&mut bonus_pool, leaver, 900_000_000, 2).unwrap();
+        add_to_last_ten(&mut bonus_pool, splitter, 900_000_000, 3).unwrap();
+        add_to_last_ten(&mut bonus_pool, splitter, 900_000_000, 4).unwrap();
+
+        let mut holder_stake = test_user_stake(holder, 0);
+        holder_stake.staked_amount = 1_800_000_000;
+        let mut leaver_stake = test_user_stake(leaver, 0);
+        leaver_stake.staked_amount = 0;
+        let mut splitter_stake = test_user_stake(splitter, 0);
+        splitter_stake.staked_amount = 900_000_000;
+
+        let entries = revalidate_last_ten(
+            &eligible_last_ten(&bonus_pool),
+            &[holder_stake, leaver_stake, splitter_stake],
+            net_threshold,
+        )
+        .unwrap();
+
+        // Leaver is dropped; splitter keeps only the most recent entry
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].investor, holder);
+        assert_eq!(entries[0].amount, 1_800_000_000);
+        assert_eq!(entries[1].investor, splitter);
+        assert_eq!(entries[1].timestamp, 4);
+
+        // Every remaining investor needs a stake account
+        assert!(revalidate_last_ten(&eligible_last_ten(&bonus_pool), &[], net_threshold).is_err());
+    }
+
+    #[test]
+    fn test_anti_sniping_min_hold() {
+        let mut bonus_pool = test_bonus_pool();
+        let investor = Pubkey::new_unique();
+        let entry = LastTenInvestor {
+            investor,
+            amount: 1_000_000_000,
+            timestamp: 1_000,
+        };
+        let user_stake = test_user_stake(investor, 0);
+
+        assert!(!anti_sniping_enabled(&bonus_pool));
+        assert!(!is_last_ten_share_forfeited(
+            &bonus_pool,
+            &entry,
+            &user_stake,
+            1_001
+        ));
+
+        // Entry made 30 seconds before distribution with a 10 minute hold
+        bonus_pool.min_hold_seconds = 600;
+        assert!(anti_sniping_enabled(&bonus_pool));
+        assert!(is_last_ten_share_forfeited(
+            &bonus_pool,
+            &entry,
+            &user_stake,
+            1_030
+        ));
+        assert!(!is_last_ten_share_forfeited(
+            &bonus_pool,
+            &entry,
+            &user_stake,
+            1_600
+        ));
+    }
+
+    #[test]
+    fn test_anti_sniping_forfeit_on_unstake() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.forfeit_on_unstake = true;
+        let investor = Pubkey::new_unique();
+        let entry = LastTenInvestor {
+            investor,
+            amount: 1_000_000_000,
+            timestamp: 1_000,
+        };
+
+        // Unstaked before entering the list: keeps the share
+        let user_stake = test_user_stake(investor, 500);
+        assert!(!is_last_ten_share_forfeited(
+            &bonus_pool,
+            &entry,
+            &user_stake,
+            2_000
+        ));
+
+        // Unstaked after entering the list: forfeits
+        let user_stake = test_user_stake(investor, 1_500);
+        assert!(is_last_ten_share_forfeited(
+            &bonus_pool,
+            &entry,
+            &user_stake,
+            2_000
+        ));
+    }
+
+    #[test]
+    fn test_flat_extension_uncapped() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.expiry_timestamp = 1_000;
+
+        let extended =
+            extend_bonus_countdown(&mut bonus_pool, 5_000_000_000, SOL_DECIMALS, 0).unwrap();
+        assert_eq!(extended, BONUS_EXTENSION_SECONDS);
+        assert_eq!(bonus_pool.expiry_timestamp, 1_000 + BONUS_EXTENSION_SECONDS);
+    }
+
+    #[test]
+    fn test_extension_capped_by_max_countdown() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.max_countdown_seconds = BONUS_MAX_COUNTDOWN;
+        let now = 1_000_000;
+
+        // 5 minutes below the cap: only 5 minutes are added
+        bonus_pool.expiry_timestamp = now + BONUS_MAX_COUNTDOWN - 300;
+        let extended =
+            extend_bonus_countdown(&mut bonus_pool, 1_000_000_000, SOL_DECIMALS, now).unwrap();
+        assert_eq!(extended, 300);
+        assert_eq!(bonus_pool.expiry_timestamp, now + BONUS_MAX_COUNTDOWN);
+
+        // At the cap: nothing more is added
+        let extended =
+            extend_bonus_countdown(&mut bonus_pool, 1_000_000_000, SOL_DECIMALS, now).unwrap();
+        assert_eq!(extended, 0);
+    }
+
+    #[test]
+    fn test_proportional_extension() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.proportional_extension = true;
+
+        // 3.5 SOL = 3 whole SOL = 45 minutes
+        let extended =
+            extend_bonus_countdown(&mut bonus_pool, 3_500_000_000, SOL_DECIMALS, 0).unwrap();
+        assert_eq!(extended, 3 * BONUS_EXTENSION_SECONDS);
+
+        // 100 SOL is capped at max_extension_seconds
+        let extended =
+            extend_bonus_countdown(&mut bonus_pool, 100_000_000_000, SOL_DECIMALS, 0).unwrap();
+        assert_eq!(extended, BONUS_MAX_EXTENSION_SECONDS);
+
+        // Token pools extend per whole token: 2.5 tokens at 6 decimals = 30 minutes
+        let extended = extend_bonus_countdown(&mut bonus_pool, 2_500_000, 6, 0).unwrap();
+        assert_eq!(extended, 2 * BONUS_EXTENSION_SECONDS);
+    }
+
+    #[test]
+    fn test_last_ten_appends_without_dedupe() {
+        let mut bonus_pool = test_bonus_pool();
+        let whale = Pubkey::new_unique();
+
+        for i in 0..MAX_LAST_TEN_INVESTORS {
+            let (position, updated) =
+                add_to_last_ten(&mut bonus_pool, whale, 1_000_000_000, i as i64).unwrap();
+            assert_eq!(position as usize, i);
+            assert!(!updated);
+        }
+
+        // Whale occupies every slot
+        assert_eq!(bonus_pool.investor_count as usize, MAX_LAST_TEN_INVESTORS);
+        assert!(ordered_last_ten(&bonus_pool)
+            .iter()
+            .all(|entry| entry.investor == whale));
+    }
+
+    #[test]
+    fn test_last_ten_dedupe_refreshes_slot() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.dedupe_last_ten = true;
+        let whale = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        add_to_last_ten(&mut bonus_pool, whale, 1_000_000_000, 1).unwrap();
+        add_to_last_ten(&mut bonus_pool, other, 2_000_000_000, 2).unwrap();
+        let (position, updated) =
+            add_to_last_ten(&mut bonus_pool, whale, 1_000_000_000, 3).unwrap();
+
+        assert!(updated);
+        assert_eq!(position, 1);
+        assert_eq!(bonus_pool.investor_count, 2);
+
+        // Whale's slot accumulated and moved to most recent
+        let entries = ordered_last_ten(&bonus_pool);
+        assert_eq!(entries[0].investor, other);
+        assert_eq!(entries[1].investor, whale);
+        assert_eq!(entries[1].amount, 2_000_000_000);
+        assert_eq!(entries[1].timestamp, 3);
+    }
+
+    #[test]
+    fn test_last_ten_dedupe_after_wrap() {
+        let mut bonus_pool = test_bonus_pool();
+        bonus_pool.dedupe_last_ten = true;
+        let investors: Vec<Pubkey> = (0..MAX_LAST_TEN_INVESTORS + 2)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        for (i, investor) in investors.iter().enumerate() {
+            add_to_last_ten(&mut bonus_pool, *investor, 1_000_000_000, i as i64).unwrap();
+        }
+
+        // Buffer wrapped: the two oldest investors were pushed out
+        let entries = ordered_last_ten(&bonus_pool);
+        assert_eq!(entries[0].investor, investors[2]);
+
+        // Refreshing the oldest remaining entry keeps the list full and ordered
+        add_to_last_ten(&mut bonus_pool, investors[2], 1_000_000_000, 100).unwrap();
+        let entries = ordered_last_ten(&bonus_pool);
+        assert_eq!(entries.len(), MAX_LAST_TEN_INVESTORS);
+        assert_eq!(entries[0].investor, investors[3]);
+        assert_eq!(entries[MAX_LAST_TEN_INVESTORS - 1].investor, investors[2]);
+        assert_eq!(entries[MAX_LAST_TEN_INVESTORS - 1].amount, 2_000_000_000);
+    }
+
+    #[test]
+    fn test_last_ten_shares_pro_rata() {
+        let mut bonus_pool = test_bonus_pool();
+        add_to_last_ten(&mut bonus_pool, Pubkey::new_unique(), 3_000_000_000, 10).unwrap();
+        add_to_last_ten(&mut bonus_pool, Pubkey::new_unique(), 1_000_000_000, 20).unwrap();
+
+        let entries = eligible_last_ten(&bonus_pool);
+        let (winners, count, paid) = calculate_last_ten_shares(&entries, 400_000_000).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(winners[0].share, 300_000_000);
+        assert_eq!(winners[1].share, 100_000_000);
+        assert_eq!(paid, 400_000_000);
+    }
+
+    #[test]
+    fn test_last_ten_shares_claimed_once() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let mut bonus_pool = test_bonus_pool();
+        add_to_last_ten(&mut bonus_pool, alice, 1_000_000_000, 10).unwrap();
+        add_to_last_ten(&mut bonus_pool, bob, 2_000_000_000, 20).unwrap();
+        add_to_last_ten(&mut bonus_pool, alice, 1_000_000_000, 30).unwrap();
+
+        let entries = eligible_last_ten(&bonus_pool);
+        let (mut winners, count, _) = calculate_last_ten_shares(&entries, 400_000_000).unwrap();
+        let winners = &mut winners[..count as usize];
+
+        // Alice is paid both of her entries, then nothing
+        assert_eq!(take_last_ten_shares(winners, &alice).unwrap(), 200_000_000);
+        assert_eq!(take_last_ten_shares(winners, &alice).unwrap(), 0);
+
+        // Forfeited shares are zeroed and never paid
+        winners[1].share = 0;
+        winners[1].forfeited = true;
+        assert_eq!(take_last_ten_shares(winners, &bob).unwrap(), 0);
+        assert_eq!(
+            take_last_ten_shares(winners, &Pubkey::new_unique()).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_last_ten_current_round_only() {
+        let mut bonus_pool = test_bonus_pool();
+        add_to_last_ten(&mut bonus_pool, Pubkey::new_unique(), 1_000_000_000, 10).unwrap();
+        add_to_last_ten(&mut bonus_pool, Pubkey::new_unique(), 2_000_000_000, 200).unwrap();
+        bonus_pool.round_start_timestamp = 100;
+
+        // Persist counts both entries
+        assert_eq!(eligible_last_ten(&bonus_pool).len(), 2);
+
+        // Current round only skips the entry from the previous round
+        bonus_pool.last_ten_policy = LastTenPolicy::CurrentRoundOnly;
+        let entries = eligible_last_ten(&bonus_pool);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, 2_000_000_000);
+
+        clear_last_ten(&mut bonus_pool);
+        assert_eq!(bonus_pool.investor_count, 0);
+        assert_eq!(bonus_pool.current_position, 0);
+    }
+
     #[test]
     fn test_referral_distribution_50_50() {
         let total = 1_000_000_000; // 1 SOL
 
-        let (stakers, carry) = calculate_referral_distribution(total).unwrap();
+        let (stakers, referrers, carry) = calculate_referral_distribution(total, 0).unwrap();
 
         // 50% each = 500_000_000
         assert_eq!(stakers, 500_000_000);
+        assert_eq!(referrers, 0);
         assert_eq!(carry, 500_000_000);
 
         assert_eq!(stakers + carry, total);
     }
+
+    #[test]
+    fn test_referral_distribution_with_referrer_share() {
+        let total = 1_000_000_000; // 1 SOL
+
+        // 30% to referrers, carved out of the carried half
+        let (stakers, referrers, carry) = calculate_referral_distribution(total, 3000).unwrap();
+
+        assert_eq!(stakers, 500_000_000);
+        assert_eq!(referrers, 300_000_000);
+        assert_eq!(carry, 200_000_000);
+
+        // Cannot exceed the carried-forward half
+        assert!(calculate_referral_distribution(total, 5001).is_err());
+    }
+
+    #[test]
+    fn test_delegation_capacity() {
+        // 90% of 100 SOL may be delegated
+        let capacity =
+            calculate_delegation_capacity(100 * LAMPORTS_PER_SOL, 0, MAX_DELEGATION_TARGET_BPS)
+                .unwrap();
+        assert_eq!(capacity, 90 * LAMPORTS_PER_SOL);
+
+        let capacity = calculate_delegation_capacity(
+            100 * LAMPORTS_PER_SOL,
+            60 * LAMPORTS_PER_SOL,
+            MAX_DELEGATION_TARGET_BPS,
+        )
+        .unwrap();
+        assert_eq!(capacity, 30 * LAMPORTS_PER_SOL);
+
+        // Over target after unstakes (or harvested rewards): nothing more to delegate
+        let capacity =
+            calculate_delegation_capacity(50 * LAMPORTS_PER_SOL, 60 * LAMPORTS_PER_SOL, 5000)
+                .unwrap();
+        assert_eq!(capacity, 0);
+
+        assert!(validate_delegation_target(MAX_DELEGATION_TARGET_BPS).is_ok());
+        assert!(validate_delegation_target(MAX_DELEGATION_TARGET_BPS + 1).is_err());
+    }
+
+    #[test]
+    fn test_unstake_cooldown_bounds() {
+        assert!(validate_unstake_cooldown(0).is_ok());
+        assert!(validate_unstake_cooldown(MAX_UNSTAKE_COOLDOWN).is_ok());
+        assert!(validate_unstake_cooldown(MAX_UNSTAKE_COOLDOWN + 1).is_err());
+        assert!(validate_unstake_cooldown(-1).is_err());
+    }
+
+    #[test]
+    fn test_stake_caps() {
+        assert!(within_cap(1_000, 500, 0).unwrap());
+        assert!(within_cap(1_000, 500, 1_500).unwrap());
+        assert!(!within_cap(1_000, 501, 1_500).unwrap());
+        assert!(within_cap(u64::MAX, 1, 0).unwrap());
+        assert!(within_cap(u64::MAX, 1, 1_500).is_err());
+    }
+
+    #[test]
+    fn test_pause_flags() {
+        assert!(validate_pause_flags(pause_flags::STAKE).is_ok());
+        assert!(validate_pause_flags(pause_flags::ALL).is_ok());
+        assert!(validate_pause_flags(0).is_err());
+        assert!(validate_pause_flags(1 << 5).is_err());
+    }
+
+    fn test_staking_pool() -> StakingPool {
+        StakingPool {
+            config: Pubkey::default(),
+            total_staked: 1_000_000_000,
+            reward_per_share: 0,
+            last_update_timestamp: 0,
+            total_stakers: 1,
+            bump: 0,
+            pool_id: 0,
+            fee_schedule: FeeSchedule::default(),
+            mint: Pubkey::default(),
+            vault: Pubkey::default(),
+            token_program: Pubkey::default(),
+            receipt_mint: Pubkey::default(),
+            total_shares: 0,
+            unstake_fee_curve: UnstakeFeeCurve::default(),
+            total_weighted_shares: 1_000_000_000,
+            unstake_cooldown_seconds: 0,
+            total_pending_unstake: 0,
+            delegation_target_bps: 0,
+            total_delegated: 0,
+            stake_account_count: 0,
+            max_total_staked: 0,
+            max_stake_per_user: 0,
+            allowlist_enabled: false,
+            decimals: SOL_DECIMALS,
+        }
+    }
+
+    #[test]
+    fn test_pause_authorization() {
+        let authority = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let mut global_config = GlobalConfig {
+            authority,
+            treasury: Pubkey::default(),
+            material_dart_wallet: Pubkey::default(),
+            pause_flags: 0,
+            guardian: Pubkey::default(),
+            is_initialized: true,
+            bump: 0,
+            pool_count: 1,
+            allowlist_merkle_root: [0; 32],
+        };
+
+        // Without a guardian nobody but the authority may pause
+        assert!(
+            apply_pause_flags(&mut global_config, &guardian, pause_flags::STAKE, true).is_err()
+        );
+        assert!(apply_pause_flags(
+            &mut global_config,
+            &Pubkey::default(),
+            pause_flags::STAKE,
+            true
+        )
+        .is_err());
+
+        // The guardian may pause but not unpause
+        global_config.guardian = guardian;
+        apply_pause_flags(&mut global_config, &guardian, pause_flags::STAKE, true).unwrap();
+        assert!(global_config.is_paused(pause_flags::STAKE));
+        assert!(
+            apply_pause_flags(&mut global_config, &guardian, pause_flags::STAKE, false).is_err()
+        );
+        assert!(global_config.is_paused(pause_flags::STAKE));
+
+        // Other wallets may do neither
+        let other = Pubkey::new_unique();
+        assert!(apply_pause_flags(&mut global_config, &other, pause_flags::CLAIM, true).is_err());
+        assert!(apply_pause_flags(&mut global_config, &other, pause_flags::STAKE, false).is_err());
+
+        // The authority may do both, flag by flag
+        apply_pause_flags(&mut global_config, &authority, pause_flags::UNSTAKE, true).unwrap();
+        apply_pause_flags(&mut global_config, &authority, pause_flags::STAKE, false).unwrap();
+        assert_eq!(global_config.pause_flags, pause_flags::UNSTAKE);
+    }
+
+    #[test]
+    fn test_allowlist_merkle_proof() {
+        fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+            let (left, right) = if a <= b { (a, b) } else { (b, a) };
+            hashv(&[&[MERKLE_NODE_PREFIX], left.as_ref(), right.as_ref()]).to_bytes()
+        }
+
+        let users: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = users.iter().map(allowlist_leaf).collect();
+        let left = node(leaves[0], leaves[1]);
+        let root = node(left, leaves[2]);
+
+        assert!(verify_merkle_proof(
+            &root,
+            leaves[0],
+            &[leaves[1], leaves[2]]
+        ));
+        assert!(verify_merkle_proof(
+            &root,
+            leaves[1],
+            &[leaves[0], leaves[2]]
+        ));
+        assert!(verify_merkle_proof(&root, leaves[2], &[left]));
+
+        // Wrong wallet, wrong sibling or truncated proof
+        let outsider = allowlist_leaf(&Pubkey::new_unique());
+        assert!(!verify_merkle_proof(
+            &root,
+            outsider,
+            &[leaves[1], leaves[2]]
+        ));
+        assert!(!verify_merkle_proof(
+            &root,
+            leaves[0],
+            &[leaves[2], leaves[1]]
+        ));
+        assert!(!verify_merkle_proof(&root, leaves[0], &[leaves[1]]));
+    }
+
+    #[test]
+    fn test_lockup_weighting() {
+        let user = Pubkey::new_unique();
+        let mut user_stake = test_user_stake(user, 0);
+        let mut pool = test_staking_pool();
+
+        // 180-day lockup doubles the weight
+        apply_lockup(&mut user_stake, LockupTier::Days180, 100).unwrap();
+        refresh_weighted_shares(&mut user_stake, &mut pool, 100).unwrap();
+        assert_eq!(user_stake.unlock_timestamp, 100 + LOCKUP_180_DAYS);
+        assert_eq!(user_stake.weighted_shares, 2_000_000_000);
+        assert_eq!(pool.total_weighted_shares, 2_000_000_000);
+
+        // Cannot downgrade while locked; unlocked top-ups join the lockup
+        assert!(apply_lockup(&mut user_stake, LockupTier::Days30, 200).is_err());
+        apply_lockup(&mut user_stake, LockupTier::None, 200).unwrap();
+        assert_eq!(user_stake.lockup_tier, LockupTier::Days180);
+
+        // Expired lockup drops back to 1x
+        let unlocked_at = user_stake.unlock_timestamp;
+        refresh_weighted_shares(&mut user_stake, &mut pool, unlocked_at).unwrap();
+        assert_eq!(user_stake.lockup_tier, LockupTier::None);
+        assert_eq!(user_stake.weighted_shares, 1_000_000_000);
+        assert_eq!(pool.total_weighted_shares, 1_000_000_000);
+
+        // After expiry any tier may be chosen again
+        apply_lockup(&mut user_stake, LockupTier::Days30, unlocked_at).unwrap();
+        assert_eq!(user_stake.unlock_timestamp, unlocked_at + LOCKUP_30_DAYS);
+    }
+
+    #[test]
+    fn test_settle_rewards_across_lockup_expiry() {
+        let user = Pubkey::new_unique();
+        let mut user_stake = test_user_stake(user, 0);
+        let mut pool = test_staking_pool();
+        pool.total_weighted_shares = 0;
+        user_stake.weighted_shares = 0;
+        user_stake.reward_debt = 0;
+
+        apply_lockup(&mut user_stake, LockupTier::Days180, 0).unwrap();
+        refresh_weighted_shares(&mut user_stake, &mut pool, 0).unwrap();
+        assert_eq!(user_stake.weighted_shares, 2_000_000_000);
+
+        // 1 lamport per weighted share earned while boosted
+        pool.reward_per_share = REWARD_PRECISION;
+        assert_eq!(
+            get_pending_rewards(&user_stake, &pool).unwrap(),
+            2_000_000_000
+        );
+
+        // Refreshing after expiry keeps those rewards but earns at 1x from then on
+        let unlocked_at = user_stake.unlock_timestamp;
+        settle_rewards(&mut user_stake, &pool).unwrap();
+        refresh_weighted_shares(&mut user_stake, &mut pool, unlocked_at).unwrap();
+        user_stake.reward_debt =
+            calculate_reward_debt(user_stake.weighted_shares, pool.reward_per_share).unwrap();
+        assert_eq!(user_stake.accrued_rewards, 2_000_000_000);
+        assert_eq!(user_stake.weighted_shares, 1_000_000_000);
+        assert_eq!(pool.total_weighted_shares, 1_000_000_000);
+        assert_eq!(
+            get_pending_rewards(&user_stake, &pool).unwrap(),
+            2_000_000_000
+        );
+
+        pool.reward_per_share = 2 * REWARD_PRECISION;
+        assert_eq!(
+            get_pending_rewards(&user_stake, &pool).unwrap(),
+            3_000_000_000
+        );
+    }
+
+    fn test_referral_pool() -> ReferralPool {
+        ReferralPool {
+            staking_pool: Pubkey::default(),
+            balance: 0,
+            next_distribution_timestamp: 0,
+            last_distribution_timestamp: 0,
+            total_distributed: 0,
+            bump: 0,
+            referrer_share_bps: 3000,
+            current_epoch: 0,
+            epoch_referred_volume: 0,
+            last_epoch_referrer_allocation: 0,
+            last_epoch_referred_volume: 0,
+            last_epoch_referrer_settled: 0,
+            referrer_claimable: 0,
+        }
+    }
+
+    fn test_referrer(referral_pool: &ReferralPool) -> ReferrerAccount {
+        ReferrerAccount {
+            referrer: Pubkey::new_unique(),
+            referral_count: 0,
+            total_earned: 0,
+            created_at: 0,
+            bump: 0,
+            volume_epoch: referral_pool.current_epoch,
+            epoch_volume: 0,
+            total_volume: 0,
+            claimable: 0,
+            referral_pool: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_referrer_settlement_pro_rata() {
+        let mut pool = test_referral_pool();
+        let mut alice = test_referrer(&pool);
+        let mut bob = test_referrer(&pool);
+
+        // Alice brings 3 SOL, Bob 1 SOL
+        record_referred_volume(&mut alice, &mut pool, 3_000_000_000).unwrap();
+        record_referred_volume(&mut bob, &mut pool, 1_000_000_000).unwrap();
+        assert_eq!(pool.epoch_referred_volume, 4_000_000_000);
+
+        // Close the period with 0.4 SOL for referrers
+        pool.last_epoch_referrer_allocation = 400_000_000;
+        pool.last_epoch_referred_volume = pool.epoch_referred_volume;
+        pool.epoch_referred_volume = 0;
+        pool.current_epoch += 1;
+
+        assert_eq!(
+            settle_referrer_rewards(&mut alice, &mut pool).unwrap(),
+            300_000_000
+        );
+        assert_eq!(
+            settle_referrer_rewards(&mut bob, &mut pool).unwrap(),
+            100_000_000
+        );
+
+        // Settling twice is a no-op
+        assert_eq!(settle_referrer_rewards(&mut alice, &mut pool).unwrap(), 0);
+
+        assert_eq!(alice.claimable, 300_000_000);
+        assert_eq!(bob.claimable, 100_000_000);
+        assert_eq!(pool.referrer_claimable, 400_000_000);
+        assert_eq!(pool.last_epoch_referrer_settled, 400_000_000);
+        assert_eq!(pool.total_distributed, 400_000_000);
+    }
+
+    #[test]
+    fn test_referrer_stale_volume_not_settled() {
+        let mut pool = test_referral_pool();
+        let mut alice = test_referrer(&pool);
+
+        record_referred_volume(&mut alice, &mut pool, 1_000_000_000).unwrap();
+
+        // Two periods pass before Alice settles
+        pool.last_epoch_referrer_allocation = 100_000_000;
+        pool.last_epoch_referred_volume = 1_000_000_000;
+        pool.current_epoch += 2;
+
+        assert_eq!(settle_referrer_rewards(&mut alice, &mut pool).unwrap(), 0);
+        assert_eq!(pool.total_distributed, 0);
+        assert_eq!(alice.epoch_volume, 0);
+        assert_eq!(alice.volume_epoch, pool.current_epoch);
+    }
 }
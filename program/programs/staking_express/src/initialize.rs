@@ -1,6 +1,5 @@
 use anchor_lang::prelude::*;
 
-use crate::constants::*;
 use crate::errors::StakingError;
 use crate::events::*;
 use crate::helpers::*;
@@ -24,12 +23,12 @@ pub struct Initialize<'info> {
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
-    /// Main staking pool
+    /// First staking pool (pool_id 0)
     #[account(
         init,
         payer = authority,
         space = StakingPool::LEN,
-        seeds = [seeds::STAKING_POOL],
+        seeds = [seeds::STAKING_POOL, 0u64.to_le_bytes().as_ref()],
         bump
     )]
     pub staking_pool: Account<'info, StakingPool>,
@@ -39,7 +38,7 @@ pub struct Initialize<'info> {
         init,
         payer = authority,
         space = BonusPool::LEN,
-        seeds = [seeds::BONUS_POOL],
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
         bump
     )]
     pub bonus_pool: Account<'info, BonusPool>,
@@ -49,7 +48,7 @@ pub struct Initialize<'info> {
         init,
         payer = authority,
         space = ReferralPool::LEN,
-        seeds = [seeds::REFERRAL_POOL],
+        seeds = [seeds::REFERRAL_POOL, staking_pool.key().as_ref()],
         bump
     )]
     pub referral_pool: Account<'info, ReferralPool>,
@@ -91,38 +90,35 @@ pub fn initialize_handler(ctx: Context<Initialize>) -> Result<()> {
     global_config.authority = authority;
     global_config.treasury = treasury;
     global_config.material_dart_wallet = material_dart_wallet;
-    global_config.paused = false;
+    global_config.pause_flags = 0;
+    global_config.guardian = Pubkey::default();
+    global_config.allowlist_merkle_root = [0; 32];
     global_config.is_initialized = true;
     global_config.bump = ctx.bumps.global_config;
 
-    // Initialize StakingPool
-    staking_pool.config = global_config.key();
-    staking_pool.total_staked = 0;
-    staking_pool.reward_per_share = 0;
-    staking_pool.last_update_timestamp = current_timestamp;
-    staking_pool.bump = ctx.bumps.staking_pool;
-
-    // Initialize BonusPool with 12-hour countdown
-    bonus_pool.staking_pool = staking_pool.key();
-    bonus_pool.balance = 0;
-    bonus_pool.expiry_timestamp = current_timestamp
-        .checked_add(BONUS_INITIAL_COUNTDOWN)
-        .ok_or(StakingError::MathOverflow)?;
-    bonus_pool.last_investment_timestamp = current_timestamp;
-    bonus_pool.last_ten_investors = [LastTenInvestor::default(); MAX_LAST_TEN_INVESTORS];
-    bonus_pool.current_position = 0;
-    bonus_pool.investor_count = 0;
-    bonus_pool.bump = ctx.bumps.bonus_pool;
-
-    // Initialize ReferralPool with 30-day distribution period
-    referral_pool.staking_pool = staking_pool.key();
-    referral_pool.balance = 0;
-    referral_pool.next_distribution_timestamp = current_timestamp
-        .checked_add(REFERRAL_DISTRIBUTION_PERIOD)
-        .ok_or(StakingError::MathOverflow)?;
-    referral_pool.last_distribution_timestamp = current_timestamp;
-    referral_pool.total_distributed = 0;
-    referral_pool.bump = ctx.bumps.referral_pool;
+    // Initialize pool 0 with the default fee schedule (10%)
+    let staking_pool_key = staking_pool.key();
+    init_staking_pool(
+        staking_pool,
+        global_config.key(),
+        0,
+        FeeSchedule::default(),
+        ctx.bumps.staking_pool,
+        current_timestamp,
+    );
+    init_bonus_pool(
+        bonus_pool,
+        staking_pool_key,
+        ctx.bumps.bonus_pool,
+        current_timestamp,
+    )?;
+    init_referral_pool(
+        referral_pool,
+        staking_pool_key,
+        ctx.bumps.referral_pool,
+        current_timestamp,
+    )?;
+    global_config.pool_count = 1;
 
     // Emit initialization event
     emit!(ProtocolInitialized {
@@ -8,22 +8,37 @@ pub mod fees;
 pub mod helpers;
 pub mod math;
 pub mod state;
+pub mod token_vault;
 
 // Instruction modules
+pub mod admin;
+pub mod allowlist;
 pub mod bonus_pool;
 pub mod claim_rewards;
+pub mod create_pool;
+pub mod delegation;
+pub mod emergency_withdraw;
 pub mod initialize;
+pub mod pool_config;
 pub mod referral;
+pub mod refresh_lockup;
 pub mod stake;
 pub mod unstake;
 
 // Re-exports for cleaner imports
+pub use admin::*;
+pub use allowlist::*;
 pub use bonus_pool::*;
 pub use claim_rewards::*;
+pub use create_pool::*;
+pub use delegation::*;
+pub use emergency_withdraw::*;
 pub use errors::*;
 pub use events::*;
 pub use initialize::*;
+pub use pool_config::*;
 pub use referral::*;
+pub use refresh_lockup::*;
 pub use stake::*;
 pub use state::*;
 pub use unstake::*;
@@ -34,9 +49,16 @@ declare_id!("E22THkjryJG3wskBBFQLqKpB4nPkAVUdLZHY1WMLn8gy");
 /// Staking Express - Production-grade Solana staking protocol
 ///
 /// Economic Model:
-/// - 10% fee on stake/unstake: 700 BPS stakers, 100 BPS platform, 100 BPS bonus, 50 BPS referral, 50 BPS Material Dart
-/// - Bonus pool: 12h countdown, 15min extension per 1 SOL, 40/40/20 distribution
-/// - Referral pool: 30-day distribution, 50/50 split
+/// - Multiple staking pools sharing one GlobalConfig, each with its own bonus pool,
+///   referral pool and fee schedule
+/// - Pools denominated in SOL or an SPL token (Token / Token-2022)
+/// - Optional liquid receipt tokens (share-price rewards, transferable positions)
+/// - SOL pools may delegate up to 90% of total staked to validators; epoch rewards
+///   are harvested into staker rewards
+/// - Optional stake lockups boost a position's share of staker rewards
+/// - Default 10% fee on stake/unstake: 700 BPS stakers, 100 BPS platform, 100 BPS bonus, 50 BPS referral, 50 BPS Material Dart
+/// - Bonus pool: 12h countdown, 15min extension per 1 SOL (capped at 24h ahead), 40/40/20 distribution
+/// - Referral pool: 30-day distribution, 50% stakers, optional referrer share, rest carried
 ///
 /// Architecture:
 /// - Uses PDAs for deterministic account addresses
@@ -49,70 +71,395 @@ pub mod staking_express {
 
     /// Initialize the staking protocol
     ///
-    /// Creates GlobalConfig, StakingPool, BonusPool, and ReferralPool accounts.
+    /// Creates GlobalConfig and pool 0 (StakingPool, BonusPool, ReferralPool)
+    /// with the default fee schedule.
     /// Sets up authority, treasury, and Material Dart wallet.
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         initialize_handler(ctx)
     }
 
+    /// Pause or unpause individual operations
+    ///
+    /// The authority may pause and unpause; the guardian may only pause.
+    ///
+    /// `flags` is a bitmask of stake (1), unstake (2), claim (4), bonus
+    /// distribution (8) and referral distribution (16). Pausing only stake
+    /// stops deposits while users can still exit and claim; while unstake is
+    /// paused users can exit through emergency_withdraw.
+    pub fn set_pause_flags(ctx: Context<SetPaused>, flags: u8, paused: bool) -> Result<()> {
+        set_pause_flags_handler(ctx, flags, paused)
+    }
+
+    /// Set or remove the guardian (authority only)
+    ///
+    /// The guardian is a hot key that can pause operations but cannot unpause
+    /// or change any other configuration. Pass Pubkey::default() to remove it.
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        set_guardian_handler(ctx, guardian)
+    }
+
+    /// Create an additional staking pool (authority only)
+    ///
+    /// The pool gets the next pool_id and its own BonusPool and ReferralPool.
+    /// Total fee of the schedule may not exceed 2000 BPS.
+    pub fn create_pool(ctx: Context<CreatePool>, fee_schedule: FeeSchedule) -> Result<()> {
+        create_pool_handler(ctx, fee_schedule)
+    }
+
+    /// Create a staking pool denominated in an SPL token (authority only)
+    ///
+    /// Supports Token and Token-2022 mints (without transfer fee, transfer hook
+    /// or non-transferable extensions). The pool's vault, owned by the staking
+    /// pool PDA, holds stakes, rewards and the bonus/referral pool balances;
+    /// fees, bonus and referral mechanics match SOL pools. Token pool
+    /// instructions take the optional mint, vault, token program and token
+    /// accounts.
+    pub fn create_token_pool(
+        ctx: Context<CreateTokenPool>,
+        fee_schedule: FeeSchedule,
+    ) -> Result<()> {
+        create_token_pool_handler(ctx, fee_schedule)
+    }
+
+    /// Turn an empty pool into a liquid pool (authority only)
+    ///
+    /// Creates a receipt token mint (authority = staking pool PDA) with the
+    /// pool asset's decimals. Stakes then mint receipt shares and unstakes burn
+    /// them; rewards raise the share price instead of reward_per_share, so
+    /// positions are transferable. The liquid pool instructions take the optional
+    /// receipt mint, receipt token account and receipt token program.
+    pub fn create_receipt_mint(ctx: Context<CreateReceiptMint>, decimals: u8) -> Result<()> {
+        create_receipt_mint_handler(ctx, decimals)
+    }
+
+    /// Set the share of total staked a SOL pool delegates to validators (authority only)
+    ///
+    /// Max 9000 BPS; the rest stays in the pool account as a liquidity buffer
+    /// for unstakes and reward claims. 0 disables new delegations.
+    pub fn update_delegation_config(
+        ctx: Context<UpdateDelegationConfig>,
+        delegation_target_bps: u16,
+    ) -> Result<()> {
+        update_delegation_config_handler(ctx, delegation_target_bps)
+    }
+
+    /// Delegate pooled SOL to a validator (authority only)
+    ///
+    /// Creates the pool's next native stake account (staker and withdrawer =
+    /// staking pool PDA, rent reserve paid by the authority), funds it with
+    /// `amount` lamports from the pool and delegates it to the vote account.
+    /// Total delegated may not exceed delegation_target_bps of total staked.
+    pub fn delegate_stake(ctx: Context<DelegateStake>, amount: u64) -> Result<()> {
+        delegate_stake_handler(ctx, amount)
+    }
+
+    /// Harvest validator rewards of a stake account (callable by anyone)
+    ///
+    /// Lamports above the recorded delegation are distributed to stakers via
+    /// reward_per_share (share price in liquid pools) and stay delegated.
+    pub fn harvest_stake_rewards(ctx: Context<HarvestStakeRewards>) -> Result<()> {
+        harvest_stake_rewards_handler(ctx)
+    }
+
+    /// Deactivate a validator stake (authority only; anyone while unstake is paused)
+    ///
+    /// Used to rebalance or to free liquidity for large unstakes; the stake
+    /// becomes withdrawable after the cooldown epoch. While unstake is paused,
+    /// users can unwind delegations themselves to exit through emergency_withdraw.
+    pub fn deactivate_stake(ctx: Context<DeactivateStake>) -> Result<()> {
+        deactivate_stake_handler(ctx)
+    }
+
+    /// Withdraw a deactivated validator stake back into the pool (authority only;
+    /// anyone while unstake is paused)
+    ///
+    /// Principal and unharvested rewards return to the pool account, the rent
+    /// reserve to the authority; the ValidatorStake record is closed.
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        withdraw_stake_handler(ctx)
+    }
+
+    /// Set a pool's unstake fee curve (authority only)
+    ///
+    /// When enabled, the full fee schedule applies for full_fee_seconds after a
+    /// position's stake-weighted deposit time, then the total fee decays linearly
+    /// over decay_seconds to floor_bps. Liquid pools always charge the full fee.
+    pub fn update_unstake_fee_curve(
+        ctx: Context<UpdatePoolConfig>,
+        curve: UnstakeFeeCurve,
+    ) -> Result<()> {
+        update_unstake_fee_curve_handler(ctx, curve)
+    }
+
+    /// Set a pool's unstake cooldown (authority only)
+    ///
+    /// With a cooldown, unstake is disabled and users exit through
+    /// request_unstake and withdraw_unstaked. Max 30 days; 0 allows instant unstake.
+    pub fn update_unstake_cooldown(
+        ctx: Context<UpdatePoolConfig>,
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        update_unstake_cooldown_handler(ctx, cooldown_seconds)
+    }
+
+    /// Set a pool's TVL and per-user stake caps (authority only)
+    ///
+    /// Caps apply to net stake amounts and only block new stakes; 0 disables a
+    /// cap. Used for a guarded launch, raising the caps as confidence grows.
+    pub fn update_stake_caps(
+        ctx: Context<UpdatePoolConfig>,
+        max_total_staked: u64,
+        max_stake_per_user: u64,
+    ) -> Result<()> {
+        update_stake_caps_handler(ctx, max_total_staked, max_stake_per_user)
+    }
+
+    /// Enable or disable allowlist-gated staking on a pool (authority only)
+    ///
+    /// While enabled, stake requires the position owner's AllowlistEntry (added
+    /// by the authority or claimed with a Merkle proof). Unstaking and claims are
+    /// never gated.
+    pub fn update_allowlist_mode(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+        update_allowlist_mode_handler(ctx, enabled)
+    }
+
+    /// Add a user to a pool's allowlist (authority only)
+    pub fn add_allowlist_entry(ctx: Context<AddAllowlistEntry>, user: Pubkey) -> Result<()> {
+        add_allowlist_entry_handler(ctx, user)
+    }
+
+    /// Remove a user from a pool's allowlist (authority only)
+    ///
+    /// Closes the entry (rent to the authority); existing positions can still
+    /// be unstaked.
+    pub fn remove_allowlist_entry(ctx: Context<RemoveAllowlistEntry>) -> Result<()> {
+        remove_allowlist_entry_handler(ctx)
+    }
+
+    /// Set the allowlist Merkle root (authority only)
+    ///
+    /// Wallets in the tree can claim their own AllowlistEntry in any
+    /// allowlist-gated pool. Leaves are sha256(0x00 || wallet), nodes
+    /// sha256(0x01 || lower || higher). Pass [0; 32] to disable claims;
+    /// entries already claimed stay until removed.
+    pub fn set_allowlist_root(ctx: Context<SetAllowlistRoot>, root: [u8; 32]) -> Result<()> {
+        set_allowlist_root_handler(ctx, root)
+    }
+
+    /// Claim the caller's AllowlistEntry for a pool with a Merkle proof
+    ///
+    /// The caller pays for the entry. At most 20 proof nodes.
+    pub fn claim_allowlist_entry(
+        ctx: Context<ClaimAllowlistEntry>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        claim_allowlist_entry_handler(ctx, proof)
+    }
+
     /// Stake SOL into the pool
     ///
-    /// Applies 10% fee:
+    /// Applies the pool's fee schedule (10% by default):
     /// - 700 BPS → All stakers (via reward_per_share)
     /// - 100 BPS → Platform treasury
     /// - 100 BPS → Bonus pool
     /// - 50 BPS → Referrer or referral pool
     /// - 50 BPS → Material Dart team
     ///
-    /// Extends bonus countdown +15min if stake >= 1 SOL
-    /// Adds to last-10 circular buffer if stake >= 1 SOL
-    pub fn stake(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
-        stake_handler(ctx, gross_amount)
+    /// Extends bonus countdown +15min if stake >= 1 SOL (or +15min per whole SOL
+    /// in proportional mode), never beyond max_countdown_seconds ahead of now.
+    /// Only stakes >= 1 SOL reset inactivity; after a distribution the first such
+    /// stake starts the next round. Expired rounds ignore stakes until distributed.
+    /// Adds to last-10 circular buffer if stake >= last_ten_threshold (1 SOL)
+    /// (refreshes the investor's existing slot in dedupe mode)
+    ///
+    /// An optional lockup (30/90/180 days) boosts the position's reward weight
+    /// (1.25x/1.5x/2x of staked_amount) and blocks unstaking until it expires.
+    /// A lockup covers the whole position and can only be extended or raised;
+    /// the boost ends when an expired position is next touched or refreshed
+    /// through refresh_lockup. Not available in liquid pools.
+    pub fn stake(ctx: Context<Stake>, gross_amount: u64, lockup_tier: LockupTier) -> Result<()> {
+        stake_handler(ctx, gross_amount, lockup_tier)
+    }
+
+    /// Stake on behalf of another wallet
+    ///
+    /// The signer pays the stake and fees; the position (UserStakeState, and
+    /// receipt shares in liquid pools) belongs to the beneficiary, who alone can
+    /// unstake or claim. Same fees and bonus mechanics as stake, without a lockup.
+    /// The beneficiary's unclaimed rewards are carried over, not compounded.
+    pub fn stake_for(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
+        stake_for_handler(ctx, gross_amount)
     }
 
     /// Unstake SOL from the pool
     ///
-    /// Applies 10% fee on unstake amount (identical to stake fee structure).
-    /// Pending rewards are transferred separately WITHOUT fees.
-    pub fn unstake(ctx: Context<Unstake>, gross_amount: u64) -> Result<()> {
-        unstake_handler(ctx, gross_amount)
+    /// Applies the pool's fee schedule on unstake amount (identical to stake),
+    /// reduced by the pool's unstake fee curve for older positions.
+    /// Pending rewards are transferred separately WITHOUT fees, to the
+    /// position's reward recipient if one is set.
+    /// Locked positions cannot be unstaked before their unlock timestamp.
+    ///
+    /// In liquid pools `amount` is the number of receipt shares to burn; the
+    /// user receives their value at the current share price, less fees.
+    /// SOL payouts fail with InsufficientLiquidity while the pool's undelegated
+    /// balance cannot cover them.
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        unstake_handler(ctx, amount)
+    }
+
+    /// Request an unstake through the withdrawal queue
+    ///
+    /// Same fees, rewards and accounts as unstake, but the net amount stops
+    /// earning and is held for withdraw_unstaked until the pool's cooldown has
+    /// elapsed. A new request restarts the cooldown for the whole queued amount.
+    /// Required in pools with an unstake cooldown.
+    pub fn request_unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        request_unstake_handler(ctx, amount)
+    }
+
+    /// Withdraw a queued unstake once its cooldown has elapsed
+    pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+        withdraw_unstaked_handler(ctx)
+    }
+
+    /// Withdraw a position's principal while unstaking is paused
+    ///
+    /// Returns staked_amount plus any queued unstake with NO FEE, ignoring
+    /// lockups and cooldowns; unclaimed rewards are forfeited and the position
+    /// is zeroed. Liquid pools redeem the holder's entire receipt balance.
+    /// Guarantees a pause cannot hold principal hostage: delegated SOL can be
+    /// returned to the pool by anyone through deactivate_stake and withdraw_stake.
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
+        emergency_withdraw_handler(ctx)
     }
 
     /// Claim accumulated staking rewards
     ///
     /// Transfers pending rewards to user (NO FEE on rewards).
     /// Updates reward debt to prevent double-claiming.
+    /// Liquid pools accrue rewards in the share price instead (nothing to claim).
     pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
         claim_rewards_handler(ctx)
     }
 
+    /// Drop the boost of an expired lockup (callable by anyone)
+    ///
+    /// Positions otherwise keep their lockup multiplier until the owner next
+    /// touches them. Rewards earned so far are kept as accrued rewards (paid by
+    /// the next claim or unstake); from then on the position earns at 1x.
+    pub fn refresh_lockup(ctx: Context<RefreshLockup>) -> Result<()> {
+        refresh_lockup_handler(ctx)
+    }
+
+    /// Set a persistent reward recipient for the caller's position
+    ///
+    /// claim_rewards and the reward leg of unstake then pay this wallet (and
+    /// its token account in token pools) instead of the owner; principal still
+    /// returns to the owner. Pubkey::default() or the owner's key clears it.
+    /// The recipient must be passed and be a funded system-owned wallet.
+    pub fn set_reward_recipient(
+        ctx: Context<SetRewardRecipient>,
+        reward_recipient: Pubkey,
+    ) -> Result<()> {
+        set_reward_recipient_handler(ctx, reward_recipient)
+    }
+
     /// Distribute bonus pool (callable by anyone when conditions met)
     ///
-    /// Triggers when:
+    /// Triggers when the round is Expired:
     /// - Countdown expires (12 hours), OR
-    /// - 6 hours of inactivity
+    /// - 6 hours without a stake >= 1 SOL
     ///
-    /// Distribution:
-    /// - 40% → Last 10 investors (pro-rata)
+    /// Distribution (BonusConfig defaults, tunable by authority):
+    /// - 40% → Last 10 investors (pro-rata to net stake, capped by current stake;
+    ///   entries whose investor's stake fell below the threshold are dropped)
     /// - 40% → All stakers (via reward_per_share)
     /// - 20% → Carry forward to next round
     ///
-    /// Countdown resets to 12 hours, last-10 list persists
+    /// In LastInvestorJackpot mode, on countdown expiry the most recent
+    /// qualifying last-10 investor first takes jackpot_bps; the rest is split as above.
+    ///
+    /// Anti-sniping (optional): last-10 shares are forfeited and carried forward
+    /// if held less than min_hold_seconds or unstaked since entering.
+    ///
+    /// Remaining accounts: UserStakeState of each last-10 investor.
+    ///
+    /// The round moves to Distributed; the next stake >= 1 SOL starts a new
+    /// 12-hour countdown. The last-10 list persists, is cleared,
+    /// or is limited to the current round depending on `last_ten_policy`.
+    /// Closes the current round into a numbered BonusRound record.
     pub fn distribute_bonus_pool(ctx: Context<DistributeBonusPool>) -> Result<()> {
         distribute_bonus_pool_handler(ctx)
     }
 
+    /// Claim the jackpot of a closed bonus round (LastInvestorJackpot mode)
+    ///
+    /// Only the round's jackpot_winner can claim, once. The jackpot stays
+    /// reserved in the bonus pool (token pools: the vault) until claimed.
+    pub fn claim_jackpot(ctx: Context<ClaimJackpot>) -> Result<()> {
+        claim_jackpot_handler(ctx)
+    }
+
+    /// Claim a last-10 investor's share of a closed bonus round
+    ///
+    /// Pays every unclaimed, non-forfeited entry of the investor in the round,
+    /// once. Shares stay reserved in the bonus pool (token pools: the vault)
+    /// until claimed.
+    pub fn claim_last_ten_share(ctx: Context<ClaimLastTenShare>) -> Result<()> {
+        claim_last_ten_share_handler(ctx)
+    }
+
+    /// Update bonus pool settings (authority only)
+    ///
+    /// Only fields set in params are changed. A new BonusConfig must have
+    /// ratios summing to 10000 BPS and positive durations and thresholds.
+    pub fn update_bonus_settings(
+        ctx: Context<UpdateBonusSettings>,
+        params: UpdateBonusSettingsParams,
+    ) -> Result<()> {
+        update_bonus_settings_handler(ctx, params)
+    }
+
     /// Distribute referral pool (authority only)
     ///
     /// Triggers monthly (30 days) or can be forced by authority.
     ///
     /// Distribution:
     /// - 50% → All stakers (via reward_per_share)
-    /// - 50% → Carry forward to next month
+    /// - referrer_share_bps → Registered referrers (pro-rata to referred volume)
+    /// - Remainder → Carry forward to next month
+    ///
+    /// Closes the current period into a numbered ReferralEpoch record.
     pub fn distribute_referral_pool(
         ctx: Context<DistributeReferralPool>,
         force: bool,
     ) -> Result<()> {
         distribute_referral_pool_handler(ctx, force)
     }
+
+    /// Register a referrer tracking account
+    ///
+    /// Registered referrers accumulate referred volume each period and
+    /// share the referrer slice of the referral pool.
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        register_referrer_handler(ctx)
+    }
+
+    /// Claim referrer share of the referral pool
+    ///
+    /// Settles volume from the last closed period and transfers the
+    /// claimable balance. Volume must be settled within one period.
+    pub fn claim_referrer_rewards(ctx: Context<ClaimReferrerRewards>) -> Result<()> {
+        claim_referrer_rewards_handler(ctx)
+    }
+
+    /// Update the referrer share of referral distributions (authority only)
+    ///
+    /// 0 keeps the stakers-only mode; max 5000 BPS (the carried-forward half).
+    pub fn update_referral_config(
+        ctx: Context<UpdateReferralConfig>,
+        referrer_share_bps: u16,
+    ) -> Result<()> {
+        update_referral_config_handler(ctx, referrer_share_bps)
+    }
 }
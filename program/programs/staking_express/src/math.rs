@@ -1,3 +1,4 @@
+use crate::constants::SOL_DECIMALS;
 use crate::errors::StakingError;
 use anchor_lang::prelude::*;
 
@@ -118,6 +119,44 @@ pub fn calculate_reward_debt(staked_amount: u64, reward_per_share: u128) -> Resu
     safe_div_u128(earned, precision)
 }
 
+/// Calculate a position's reward weight from its lockup multiplier
+///
+/// Formula: weighted_shares = staked_amount * multiplier_bps / 10000
+pub fn calculate_weighted_shares(staked_amount: u64, multiplier_bps: u64) -> Result<u64> {
+    let weighted = (staked_amount as u128)
+        .checked_mul(multiplier_bps as u128)
+        .ok_or(StakingError::MathOverflow)?
+        / crate::constants::BPS_DENOMINATOR as u128;
+
+    u64::try_from(weighted).map_err(|_| StakingError::MathOverflow.into())
+}
+
+/// Calculate the stake-weighted average deposit time after a top-up
+///
+/// Formula: ts = (old_amount * old_ts + added * now) / (old_amount + added)
+pub fn calculate_weighted_timestamp(
+    old_amount: u64,
+    old_timestamp: i64,
+    added_amount: u64,
+    current_timestamp: i64,
+) -> Result<i64> {
+    if old_amount == 0 {
+        return Ok(current_timestamp);
+    }
+
+    let weighted = (old_amount as i128)
+        .checked_mul(old_timestamp as i128)
+        .and_then(|old| {
+            (added_amount as i128)
+                .checked_mul(current_timestamp as i128)
+                .and_then(|added| old.checked_add(added))
+        })
+        .ok_or(StakingError::MathOverflow)?;
+    let total = old_amount as i128 + added_amount as i128;
+
+    i64::try_from(weighted / total).map_err(|_| StakingError::MathOverflow.into())
+}
+
 /// Calculate pro-rata share for bonus pool distribution
 ///
 /// Formula: share = (investor_amount * total_to_distribute) / total_invested
@@ -145,6 +184,49 @@ pub fn calculate_pro_rata_share(
     Ok(share as u64)
 }
 
+/// Calculate receipt shares minted for a deposit into a liquid pool
+///
+/// Formula: shares = amount * total_shares / total_assets (1:1 for the first deposit)
+pub fn calculate_shares_for_deposit(
+    amount: u64,
+    total_assets: u64,
+    total_shares: u64,
+) -> Result<u64> {
+    if total_shares == 0 || total_assets == 0 {
+        return Ok(amount);
+    }
+
+    calculate_pro_rata_share(amount, total_assets, total_shares)
+}
+
+/// Calculate the underlying value of receipt shares in a liquid pool
+///
+/// Formula: assets = shares * total_assets / total_shares
+pub fn calculate_assets_for_shares(
+    shares: u64,
+    total_assets: u64,
+    total_shares: u64,
+) -> Result<u64> {
+    calculate_pro_rata_share(shares, total_shares, total_assets)
+}
+
+/// Scale a lamport-denominated amount to a pool asset's decimals
+///
+/// Keeps SOL-denominated minimums at the same whole-token value for mints
+/// with other decimals. Never returns 0 for a non-zero amount.
+pub fn scale_to_decimals(lamports: u64, decimals: u8) -> Result<u64> {
+    let scaled = if decimals >= SOL_DECIMALS {
+        let factor = 10u64
+            .checked_pow(u32::from(decimals - SOL_DECIMALS))
+            .ok_or(StakingError::MathOverflow)?;
+        safe_mul(lamports, factor)?
+    } else {
+        lamports / 10u64.pow(u32::from(SOL_DECIMALS - decimals))
+    };
+
+    Ok(if lamports > 0 { scaled.max(1) } else { 0 })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +270,92 @@ mod tests {
         // (10e9 * 7e10) / 1e12 = 700_000_000 (0.7 SOL)
         assert_eq!(pending, 700_000_000);
     }
+
+    #[test]
+    fn test_weighted_shares() {
+        let staked = 1_000_000_000;
+
+        assert_eq!(calculate_weighted_shares(staked, 10_000).unwrap(), staked);
+        assert_eq!(
+            calculate_weighted_shares(staked, 12_500).unwrap(),
+            1_250_000_000
+        );
+        assert_eq!(
+            calculate_weighted_shares(staked, 20_000).unwrap(),
+            2_000_000_000
+        );
+
+        // A 2x position earns twice the rewards of an unlocked one
+        let rps = calculate_reward_per_share_increase(300_000_000, 3_000_000_000).unwrap();
+        let unlocked = calculate_pending_rewards(staked, rps, 0).unwrap();
+        let locked = calculate_pending_rewards(2_000_000_000, rps, 0).unwrap();
+        assert_eq!(unlocked, 100_000_000);
+        assert_eq!(locked, 200_000_000);
+    }
+
+    #[test]
+    fn test_weighted_timestamp() {
+        // First deposit starts the clock
+        assert_eq!(
+            calculate_weighted_timestamp(0, 0, 500, 1_000).unwrap(),
+            1_000
+        );
+
+        // Equal top-up lands halfway
+        assert_eq!(
+            calculate_weighted_timestamp(1_000, 1_000, 1_000, 3_000).unwrap(),
+            2_000
+        );
+
+        // Small top-up barely moves an old position
+        assert_eq!(
+            calculate_weighted_timestamp(9_000, 0, 1_000, 10_000).unwrap(),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_share_price() {
+        // First deposit mints 1:1
+        assert_eq!(
+            calculate_shares_for_deposit(900_000_000, 0, 0).unwrap(),
+            900_000_000
+        );
+
+        // 10 SOL of shares backed by 11 SOL after fees accrued
+        let total_assets = 11_000_000_000;
+        let total_shares = 10_000_000_000;
+
+        // 1.1 SOL deposit buys 1 SOL of shares
+        let shares =
+            calculate_shares_for_deposit(1_100_000_000, total_assets, total_shares).unwrap();
+        assert_eq!(shares, 1_000_000_000);
+
+        // Redeeming 5 SOL of shares returns 5.5 SOL
+        let assets =
+            calculate_assets_for_shares(5_000_000_000, total_assets, total_shares).unwrap();
+        assert_eq!(assets, 5_500_000_000);
+    }
+
+    #[test]
+    fn test_scale_to_decimals() {
+        // SOL pools keep lamport amounts
+        assert_eq!(scale_to_decimals(10_000_000, 9).unwrap(), 10_000_000);
+
+        // 0.01 SOL minimum = 0.01 USDC (6 decimals)
+        assert_eq!(scale_to_decimals(10_000_000, 6).unwrap(), 10_000);
+
+        // 1 SOL threshold = 1 whole token with 12 decimals
+        assert_eq!(
+            scale_to_decimals(1_000_000_000, 12).unwrap(),
+            1_000_000_000_000
+        );
+
+        // 0.01 of a 0-decimal token rounds up to one base unit
+        assert_eq!(scale_to_decimals(10_000_000, 0).unwrap(), 1);
+        assert_eq!(scale_to_decimals(0, 0).unwrap(), 0);
+
+        // Overflowing scale factors are rejected
+        assert!(scale_to_decimals(1, 40).is_err());
+    }
 }
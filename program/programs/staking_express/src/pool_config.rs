@@ -0,0 +1,136 @@
+use crate::errors::StakingError;
+use crate::events::*;
+use crate::fees::*;
+use crate::helpers::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+}
+
+pub fn update_unstake_fee_curve_handler(
+    ctx: Context<UpdatePoolConfig>,
+    curve: UnstakeFeeCurve,
+) -> Result<()> {
+    validate_unstake_fee_curve(&curve)?;
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    staking_pool.unstake_fee_curve = curve;
+
+    emit!(UnstakeFeeCurveUpdated {
+        pool: staking_pool.key(),
+        curve,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!("Pool {} unstake fee curve updated", staking_pool.pool_id);
+    if curve.enabled {
+        msg!(
+            "Full fee for {}s, decaying over {}s to {} BPS",
+            curve.full_fee_seconds,
+            curve.decay_seconds,
+            curve.floor_bps
+        );
+    } else {
+        msg!("Unstake fee curve disabled");
+    }
+
+    Ok(())
+}
+
+pub fn update_unstake_cooldown_handler(
+    ctx: Context<UpdatePoolConfig>,
+    cooldown_seconds: i64,
+) -> Result<()> {
+    validate_unstake_cooldown(cooldown_seconds)?;
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    staking_pool.unstake_cooldown_seconds = cooldown_seconds;
+
+    emit!(UnstakeCooldownUpdated {
+        pool: staking_pool.key(),
+        cooldown_seconds,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!(
+        "Pool {} unstake cooldown set to {}s",
+        staking_pool.pool_id,
+        cooldown_seconds
+    );
+
+    Ok(())
+}
+
+pub fn update_stake_caps_handler(
+    ctx: Context<UpdatePoolConfig>,
+    max_total_staked: u64,
+    max_stake_per_user: u64,
+) -> Result<()> {
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    staking_pool.max_total_staked = max_total_staked;
+    staking_pool.max_stake_per_user = max_stake_per_user;
+
+    emit!(StakeCapsUpdated {
+        pool: staking_pool.key(),
+        max_total_staked,
+        max_stake_per_user,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!("Pool {} stake caps updated", staking_pool.pool_id);
+    msg!("Max total staked: {} (0 = no cap)", max_total_staked);
+    msg!("Max stake per user: {} (0 = no cap)", max_stake_per_user);
+
+    Ok(())
+}
+
+pub fn update_allowlist_mode_handler(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    staking_pool.allowlist_enabled = enabled;
+
+    emit!(AllowlistModeUpdated {
+        pool: staking_pool.key(),
+        enabled,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    if enabled {
+        msg!(
+            "Pool {} staking restricted to allowlist",
+            staking_pool.pool_id
+        );
+    } else {
+        msg!("Pool {} staking open to everyone", staking_pool.pool_id);
+    }
+
+    Ok(())
+}
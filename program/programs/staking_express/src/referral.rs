@@ -1,9 +1,12 @@
+use crate::constants::*;
 use crate::errors::StakingError;
 use crate::events::*;
 use crate::helpers::*;
 use crate::math::*;
 use crate::state::*;
+use crate::token_vault::*;
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct DistributeReferralPool<'info> {
@@ -19,14 +22,15 @@ pub struct DistributeReferralPool<'info> {
         seeds = [seeds::GLOBAL_CONFIG],
         bump = global_config.bump,
         owner = crate::ID,
-        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized,
+        constraint = !global_config.is_paused(pause_flags::REFERRAL_DISTRIBUTION) @ StakingError::PoolPaused
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
     /// Staking pool
     #[account(
         mut,
-        seeds = [seeds::STAKING_POOL],
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
         bump = staking_pool.bump,
         owner = crate::ID
     )]
@@ -35,12 +39,26 @@ pub struct DistributeReferralPool<'info> {
     /// Referral pool
     #[account(
         mut,
-        seeds = [seeds::REFERRAL_POOL],
+        seeds = [seeds::REFERRAL_POOL, staking_pool.key().as_ref()],
         bump = referral_pool.bump,
         owner = crate::ID
     )]
     pub referral_pool: Account<'info, ReferralPool>,
 
+    /// Record of the period being closed
+    #[account(
+        init,
+        payer = authority,
+        space = ReferralEpoch::LEN,
+        seeds = [
+            seeds::REFERRAL_EPOCH,
+            referral_pool.key().as_ref(),
+            referral_pool.current_epoch.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub referral_epoch: Account<'info, ReferralEpoch>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -50,6 +68,7 @@ pub fn distribute_referral_pool_handler(
 ) -> Result<()> {
     let staking_pool = &mut ctx.accounts.staking_pool;
     let referral_pool = &mut ctx.accounts.referral_pool;
+    let referral_epoch = &mut ctx.accounts.referral_epoch;
     let current_timestamp = get_current_timestamp()?;
 
     // Check if 30-day period has ended (or force distribution)
@@ -60,6 +79,15 @@ pub fn distribute_referral_pool_handler(
         StakingError::ReferralPeriodNotEnded
     );
 
+    // ========== SWEEP UNSETTLED REFERRER ALLOCATION ==========
+
+    // Referrers had the whole period to settle the previous allocation
+    let unsettled = safe_sub(
+        referral_pool.last_epoch_referrer_allocation,
+        referral_pool.last_epoch_referrer_settled,
+    )?;
+    referral_pool.balance = safe_add(referral_pool.balance, unsettled)?;
+
     // Validate referral pool has balance
     require!(
         referral_pool.balance > 0,
@@ -68,23 +96,67 @@ pub fn distribute_referral_pool_handler(
 
     let total_to_distribute = referral_pool.balance;
 
-    // Calculate distribution: 50% / 50%
-    let (to_stakers, carry_forward) = calculate_referral_distribution(total_to_distribute)?;
+    // Referrers only share the pool if someone brought in volume this period
+    let referrer_share_bps = if referral_pool.epoch_referred_volume > 0 {
+        referral_pool.referrer_share_bps
+    } else {
+        0
+    };
+
+    // Calculate distribution: 50% stakers / referrer share / remainder carried
+    let (to_stakers, to_referrers, mut carry_forward) =
+        calculate_referral_distribution(total_to_distribute, referrer_share_bps)?;
 
     // ========== DISTRIBUTE TO ALL STAKERS ==========
 
-    if to_stakers > 0 && staking_pool.total_staked > 0 {
+    let distributed_to_stakers = if to_stakers > 0 && staking_pool.total_staked > 0 {
         // Update reward_per_share for all stakers
         update_reward_per_share(staking_pool, to_stakers)?;
 
-        // Transfer from referral pool to staking pool
-        **referral_pool.to_account_info().try_borrow_mut_lamports()? -= to_stakers;
-        **staking_pool.to_account_info().try_borrow_mut_lamports()? += to_stakers;
+        // Transfer from referral pool to staking pool (token pools share one vault)
+        if !staking_pool.is_token_pool() {
+            **referral_pool.to_account_info().try_borrow_mut_lamports()? -= to_stakers;
+            **staking_pool.to_account_info().try_borrow_mut_lamports()? += to_stakers;
+        }
 
-        // Update referral pool balance
-        referral_pool.balance = carry_forward;
         referral_pool.total_distributed = safe_add(referral_pool.total_distributed, to_stakers)?;
-    }
+        to_stakers
+    } else {
+        // Nobody to pay - the stakers' half stays in the pool
+        carry_forward = safe_add(carry_forward, to_stakers)?;
+        0
+    };
+
+    // ========== RECORD CLOSED PERIOD ==========
+
+    let epoch = referral_pool.current_epoch;
+    referral_epoch.epoch = epoch;
+    referral_epoch.start_timestamp = referral_pool.last_distribution_timestamp;
+    referral_epoch.end_timestamp = current_timestamp;
+    referral_epoch.balance_in = total_to_distribute;
+    referral_epoch.swept_from_referrers = unsettled;
+    referral_epoch.to_stakers = distributed_to_stakers;
+    referral_epoch.to_referrers = to_referrers;
+    referral_epoch.carried_forward = carry_forward;
+    referral_epoch.total_staked = staking_pool.total_staked;
+    referral_epoch.referred_volume = referral_pool.epoch_referred_volume;
+    referral_epoch.bump = ctx.bumps.referral_epoch;
+
+    // ========== RESERVE REFERRER ALLOCATION ==========
+
+    // Lamports stay in the referral pool until referrers claim them; they count
+    // as distributed once settled
+    referral_pool.last_epoch_referrer_allocation = to_referrers;
+    referral_pool.last_epoch_referred_volume = referral_pool.epoch_referred_volume;
+    referral_pool.last_epoch_referrer_settled = 0;
+    referral_pool.epoch_referred_volume = 0;
+    referral_pool.current_epoch = referral_pool
+        .current_epoch
+        .checked_add(1)
+        .ok_or(StakingError::MathOverflow)?;
+
+    // Update referral pool balance
+    referral_pool.balance = carry_forward;
 
     // ========== RESET 30-DAY TIMER ==========
 
@@ -96,16 +168,23 @@ pub fn distribute_referral_pool_handler(
     // ========== EMIT EVENT ==========
 
     emit!(ReferralPoolDistributed {
-        total_distributed: safe_add(to_stakers, carry_forward)?,
-        to_stakers,
+        epoch,
+        total_distributed: total_to_distribute,
+        to_stakers: distributed_to_stakers,
+        to_referrers,
         carried_forward: carry_forward,
         next_distribution: referral_pool.next_distribution_timestamp,
         timestamp: current_timestamp,
     });
 
-    msg!("✅ Referral pool distributed!");
-    msg!("To stakers: {} lamports (50%)", to_stakers);
-    msg!("Carried forward: {} lamports (50%)", carry_forward);
+    msg!("✅ Referral pool distributed! (epoch {})", epoch);
+    msg!("To stakers: {} lamports", distributed_to_stakers);
+    msg!(
+        "To referrers: {} lamports ({} BPS)",
+        to_referrers,
+        referrer_share_bps
+    );
+    msg!("Carried forward: {} lamports", carry_forward);
     msg!(
         "Next distribution: {} (30 days)",
         referral_pool.next_distribution_timestamp
@@ -113,3 +192,245 @@ pub fn distribute_referral_pool_handler(
 
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        mut,
+        constraint = referrer.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub referrer: Signer<'info>,
+
+    /// Referral pool
+    #[account(
+        seeds = [seeds::REFERRAL_POOL, referral_pool.staking_pool.as_ref()],
+        bump = referral_pool.bump,
+        owner = crate::ID
+    )]
+    pub referral_pool: Account<'info, ReferralPool>,
+
+    /// Referrer tracking account
+    #[account(
+        init,
+        payer = referrer,
+        space = ReferrerAccount::LEN,
+        seeds = [seeds::REFERRER, referral_pool.key().as_ref(), referrer.key().as_ref()],
+        bump
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_referrer_handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referrer_key = ctx.accounts.referrer.key();
+    let referrer_account = &mut ctx.accounts.referrer_account;
+    let current_timestamp = get_current_timestamp()?;
+
+    referrer_account.referrer = referrer_key;
+    referrer_account.referral_count = 0;
+    referrer_account.total_earned = 0;
+    referrer_account.created_at = current_timestamp;
+    referrer_account.bump = ctx.bumps.referrer_account;
+    referrer_account.volume_epoch = ctx.accounts.referral_pool.current_epoch;
+    referrer_account.epoch_volume = 0;
+    referrer_account.total_volume = 0;
+    referrer_account.claimable = 0;
+    referrer_account.referral_pool = ctx.accounts.referral_pool.key();
+
+    emit!(ReferrerRegistered {
+        referrer: referrer_key,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Referrer registered: {}", referrer_key);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferrerRewards<'info> {
+    #[account(
+        mut,
+        constraint = referrer.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub referrer: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = !global_config.is_paused(pause_flags::CLAIM) @ StakingError::PoolPaused
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool the referral pool belongs to (signs token vault transfers)
+    #[account(
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Referral pool (holds reserved referrer allocations)
+    #[account(
+        mut,
+        seeds = [seeds::REFERRAL_POOL, staking_pool.key().as_ref()],
+        bump = referral_pool.bump,
+        owner = crate::ID
+    )]
+    pub referral_pool: Account<'info, ReferralPool>,
+
+    /// Referrer tracking account
+    #[account(
+        mut,
+        seeds = [seeds::REFERRER, referral_pool.key().as_ref(), referrer.key().as_ref()],
+        bump = referrer_account.bump,
+        owner = crate::ID,
+        constraint = referrer_account.referrer == referrer.key() @ StakingError::Unauthorized
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: referrer's token account (receives the rewards)
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_referrer_rewards_handler(ctx: Context<ClaimReferrerRewards>) -> Result<()> {
+    let referrer_key = ctx.accounts.referrer.key();
+    let current_timestamp = get_current_timestamp()?;
+
+    // Settle volume from the last closed period
+    settle_referrer_rewards(
+        &mut ctx.accounts.referrer_account,
+        &mut ctx.accounts.referral_pool,
+    )?;
+
+    let amount = ctx.accounts.referrer_account.claimable;
+    require!(amount > 0, StakingError::NoReferralRewardsAvailable);
+
+    // Transfer from referral pool (token pools: the vault) to referrer
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts.transfer_referrer_tokens(amount)?;
+    } else {
+        **ctx
+            .accounts
+            .referral_pool
+            .to_account_info()
+            .try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .referrer
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+    }
+
+    let referral_pool = &mut ctx.accounts.referral_pool;
+    let referrer_account = &mut ctx.accounts.referrer_account;
+    referral_pool.referrer_claimable = safe_sub(referral_pool.referrer_claimable, amount)?;
+    referrer_account.claimable = 0;
+    referrer_account.total_earned = safe_add(referrer_account.total_earned, amount)?;
+
+    emit!(ReferrerRewardsClaimed {
+        referrer: referrer_key,
+        amount,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Referral rewards claimed!");
+    msg!("Referrer: {}", referrer_key);
+    msg!("Amount: {} lamports", amount);
+
+    Ok(())
+}
+
+impl<'info> ClaimReferrerRewards<'info> {
+    /// Pay token referral rewards from the vault
+    fn transfer_referrer_tokens(&self, amount: u64) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let referrer_token_account = validate_token_account(
+            self.referrer_token_account.as_ref(),
+            &self.staking_pool.mint,
+            &self.referrer.key(),
+        )?;
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        transfer_pool_tokens(
+            &tokens,
+            tokens.vault.to_account_info(),
+            referrer_token_account.to_account_info(),
+            self.staking_pool.to_account_info(),
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+            amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateReferralConfig<'info> {
+    /// Authority only (admin-controlled)
+    #[account(
+        constraint = authority.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = authority.key() == global_config.authority @ StakingError::Unauthorized
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Referral pool
+    #[account(
+        mut,
+        seeds = [seeds::REFERRAL_POOL, referral_pool.staking_pool.as_ref()],
+        bump = referral_pool.bump,
+        owner = crate::ID
+    )]
+    pub referral_pool: Account<'info, ReferralPool>,
+}
+
+pub fn update_referral_config_handler(
+    ctx: Context<UpdateReferralConfig>,
+    referrer_share_bps: u16,
+) -> Result<()> {
+    require!(
+        referrer_share_bps <= MAX_REFERRER_SHARE_BPS,
+        StakingError::InvalidReferralConfig
+    );
+
+    let referral_pool = &mut ctx.accounts.referral_pool;
+    referral_pool.referrer_share_bps = referrer_share_bps;
+
+    emit!(ReferralConfigUpdated {
+        referrer_share_bps,
+        authority: ctx.accounts.authority.key(),
+        timestamp: get_current_timestamp()?,
+    });
+
+    msg!("Referrer share set to {} BPS", referrer_share_bps);
+
+    Ok(())
+}
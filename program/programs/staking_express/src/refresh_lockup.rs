@@ -0,0 +1,64 @@
+use crate::errors::StakingError;
+use crate::events::*;
+use crate::helpers::*;
+use crate::math::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RefreshLockup<'info> {
+    /// Staking pool
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// Position with an expired lockup (any owner)
+    #[account(
+        mut,
+        seeds = [seeds::USER_STAKE, user_stake.user.as_ref(), staking_pool.key().as_ref()],
+        bump = user_stake.bump,
+        owner = crate::ID
+    )]
+    pub user_stake: Account<'info, UserStakeState>,
+}
+
+/// Drop the boost of an expired lockup (permissionless)
+///
+/// Rewards earned so far are carried into accrued_rewards, so the owner keeps
+/// them; from now on the position earns at 1x.
+pub fn refresh_lockup_handler(ctx: Context<RefreshLockup>) -> Result<()> {
+    let current_timestamp = get_current_timestamp()?;
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    require!(
+        user_stake.unlock_timestamp != 0 && user_stake.unlock_timestamp <= current_timestamp,
+        StakingError::LockupNotExpired
+    );
+
+    let lockup_tier = user_stake.lockup_tier;
+    settle_rewards(user_stake, staking_pool)?;
+    refresh_weighted_shares(user_stake, staking_pool, current_timestamp)?;
+    user_stake.reward_debt =
+        calculate_reward_debt(user_stake.weighted_shares, staking_pool.reward_per_share)?;
+
+    emit!(LockupRefreshed {
+        user: user_stake.user,
+        pool: staking_pool.key(),
+        lockup_tier,
+        weighted_shares: user_stake.weighted_shares,
+        accrued_rewards: user_stake.accrued_rewards,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Expired lockup refreshed");
+    msg!("User: {}", user_stake.user);
+    msg!("Weighted shares: {}", user_stake.weighted_shares);
+    msg!("Accrued rewards: {} lamports", user_stake.accrued_rewards);
+
+    Ok(())
+}
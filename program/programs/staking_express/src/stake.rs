@@ -8,6 +8,8 @@ use crate::fees::*;
 use crate::helpers::*;
 use crate::math::*;
 use crate::state::*;
+use crate::token_vault::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct Stake<'info> {
@@ -17,30 +19,41 @@ pub struct Stake<'info> {
     )]
     pub user: Signer<'info>,
 
+    /// stake_for: wallet that owns the position (the user only funds it)
+    /// CHECK: Any system-owned wallet; only its key is used
+    #[account(
+        owner = anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub beneficiary: Option<UncheckedAccount<'info>>,
+
     /// Global configuration
     #[account(
         seeds = [seeds::GLOBAL_CONFIG],
         bump = global_config.bump,
         owner = crate::ID,
-        constraint = !global_config.paused @ StakingError::PoolPaused
+        constraint = !global_config.is_paused(pause_flags::STAKE) @ StakingError::PoolPaused
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
     /// Staking pool
     #[account(
         mut,
-        seeds = [seeds::STAKING_POOL],
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
         bump = staking_pool.bump,
         owner = crate::ID
     )]
     pub staking_pool: Account<'info, StakingPool>,
 
-    /// User's stake state
+    /// Position owner's stake state (the beneficiary's for stake_for)
     #[account(
         init_if_needed,
         payer = user,
         space = UserStakeState::LEN,
-        seeds = [seeds::USER_STAKE, user.key().as_ref(), staking_pool.key().as_ref()],
+        seeds = [
+            seeds::USER_STAKE,
+            beneficiary.as_ref().map_or(user.key(), |beneficiary| beneficiary.key()).as_ref(),
+            staking_pool.key().as_ref()
+        ],
         bump
     )]
     pub user_stake: Account<'info, UserStakeState>,
@@ -48,7 +61,7 @@ pub struct Stake<'info> {
     /// Bonus pool
     #[account(
         mut,
-        seeds = [seeds::BONUS_POOL],
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
         bump = bonus_pool.bump,
         owner = crate::ID
     )]
@@ -57,7 +70,7 @@ pub struct Stake<'info> {
     /// Referral pool
     #[account(
         mut,
-        seeds = [seeds::REFERRAL_POOL],
+        seeds = [seeds::REFERRAL_POOL, staking_pool.key().as_ref()],
         bump = referral_pool.bump,
         owner = crate::ID
     )]
@@ -87,122 +100,202 @@ pub struct Stake<'info> {
     )]
     pub referrer: Option<UncheckedAccount<'info>>,
 
+    /// Optional referrer tracking account (tracks referred volume for the referral pool)
+    #[account(
+        mut,
+        owner = crate::ID
+    )]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
+
+    /// Allowlisted pools: position owner's allowlist entry
+    #[account(
+        seeds = [
+            seeds::ALLOWLIST,
+            staking_pool.key().as_ref(),
+            beneficiary.as_ref().map_or(user.key(), |beneficiary| beneficiary.key()).as_ref()
+        ],
+        bump = allowlist_entry.bump,
+        owner = crate::ID
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: user's token account (source of the stake)
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: treasury's token account
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Material Dart team's token account
+    #[account(mut)]
+    pub material_dart_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: referrer's token account (if referred)
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Liquid pools: receipt token mint
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Liquid pools: position owner's receipt token account
+    #[account(mut)]
+    pub user_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Liquid pools: token program of the receipt mint
+    pub receipt_token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn stake_handler(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
+pub fn stake_handler(
+    ctx: Context<Stake>,
+    gross_amount: u64,
+    lockup_tier: LockupTier,
+) -> Result<()> {
+    require!(
+        ctx.accounts.beneficiary.is_none(),
+        StakingError::InvalidBeneficiary
+    );
+
+    process_stake(ctx, gross_amount, lockup_tier)
+}
+
+/// Stake funded by the signer into the beneficiary's position
+///
+/// The funder cannot choose a lockup, so it cannot lock someone else's position.
+pub fn stake_for_handler(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.beneficiary.is_some(),
+        StakingError::InvalidBeneficiary
+    );
+
+    process_stake(ctx, gross_amount, LockupTier::None)
+}
+
+/// Shared body of stake and stake_for
+///
+/// The signer pays the stake and fees; the position belongs to the position
+/// owner (the beneficiary, or the signer itself).
+fn process_stake(ctx: Context<Stake>, gross_amount: u64, lockup_tier: LockupTier) -> Result<()> {
     // Validate minimum stake
-    require!(gross_amount >= MIN_STAKE, StakingError::StakeTooSmall);
+    let decimals = ctx.accounts.staking_pool.decimals;
+    require!(
+        gross_amount >= scale_to_decimals(MIN_STAKE, decimals)?,
+        StakingError::StakeTooSmall
+    );
 
-    let user_key = ctx.accounts.user.key();
-    let staking_pool = &mut ctx.accounts.staking_pool;
-    let user_stake = &mut ctx.accounts.user_stake;
-    let bonus_pool = &mut ctx.accounts.bonus_pool;
-    let referral_pool = &mut ctx.accounts.referral_pool;
-    let current_timestamp = get_current_timestamp()?;
+    // Private beta: only allowlisted stakers may deposit
+    require!(
+        !ctx.accounts.staking_pool.allowlist_enabled || ctx.accounts.allowlist_entry.is_some(),
+        StakingError::NotAllowlisted
+    );
+
+    // Receipt tokens are transferable, so liquid positions cannot be locked
+    require!(
+        lockup_tier == LockupTier::None || !ctx.accounts.staking_pool.is_liquid(),
+        StakingError::LockupNotSupported
+    );
 
-    // Calculate fee breakdown (10% total)
-    let fees = calculate_stake_fee(gross_amount)?;
+    // Calculate fee breakdown (pool fee schedule, 10% by default)
+    let fees = calculate_stake_fee(gross_amount, &ctx.accounts.staking_pool.fee_schedule)?;
     verify_fee_breakdown(&fees)?;
 
+    // Guarded launch: TVL and per-user caps apply to the net stake
+    let staking_pool = &ctx.accounts.staking_pool;
+    require!(
+        within_cap(
+            staking_pool.total_staked,
+            fees.net_amount,
+            staking_pool.max_total_staked
+        )?,
+        StakingError::PoolCapExceeded
+    );
+    require!(
+        within_cap(
+            ctx.accounts.current_position()?,
+            fees.net_amount,
+            staking_pool.max_stake_per_user
+        )?,
+        StakingError::UserCapExceeded
+    );
+
     // ========== FEE DISTRIBUTION ==========
 
-    // 1. Transfer 100 BPS to treasury (platform commission)
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user.to_account_info(),
-                to: ctx.accounts.treasury.to_account_info(),
-            },
-        ),
-        fees.platform,
-    )?;
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts.transfer_stake_tokens(&fees, gross_amount)?;
+    } else {
+        ctx.accounts.transfer_stake_lamports(&fees, gross_amount)?;
+    }
 
-    // 2. Transfer 50 BPS to Material Dart team
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user.to_account_info(),
-                to: ctx.accounts.material_dart_wallet.to_account_info(),
-            },
-        ),
-        fees.material_dart,
-    )?;
+    let funder = ctx.accounts.user.key();
+    let user_key = ctx.accounts.position_owner();
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let bonus_pool = &mut ctx.accounts.bonus_pool;
+    let referral_pool = &mut ctx.accounts.referral_pool;
+    let current_timestamp = get_current_timestamp()?;
 
-    //  3. Add 100 BPS to bonus pool
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user.to_account_info(),
-                to: bonus_pool.to_account_info(),
-            },
-        ),
-        fees.bonus_pool,
-    )?;
+    // 100 BPS to bonus pool
     bonus_pool.balance = safe_add(bonus_pool.balance, fees.bonus_pool)?;
 
-    // 4. Handle referral (50 BPS)
+    // 50 BPS to referrer (paid directly) or referral pool
     let referrer_pubkey = if let Some(ref referrer) = ctx.accounts.referrer {
-        // Referrer exists - pay directly
-        transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.user.to_account_info(),
-                    to: referrer.to_account_info(),
-                },
-            ),
-            fees.referral,
-        )?;
+        // Neither the position owner nor the funder can earn referral fees on their own stake
+        require!(
+            referrer.key() != user_key && referrer.key() != funder,
+            StakingError::SelfReferral
+        );
+
+        // A position stays bound to the referrer it was opened with
+        if let Some(bound_referrer) = user_stake.referrer {
+            require!(
+                referrer.key() == bound_referrer,
+                StakingError::InvalidReferrer
+            );
+        }
+
+        // Track referred volume if the referrer is registered
+        if let Some(referrer_account) = ctx.accounts.referrer_account.as_mut() {
+            require!(
+                referrer_account.referrer == referrer.key()
+                    && referrer_account.referral_pool == referral_pool.key(),
+                StakingError::InvalidReferrer
+            );
+
+            record_referred_volume(referrer_account, referral_pool, gross_amount)?;
+            referrer_account.total_earned = safe_add(referrer_account.total_earned, fees.referral)?;
+            if user_stake.user == Pubkey::default() {
+                referrer_account.referral_count = referrer_account
+                    .referral_count
+                    .checked_add(1)
+                    .ok_or(StakingError::MathOverflow)?;
+            }
+        }
 
         Some(referrer.key())
     } else {
-        // No referrer - add to referral pool
-        transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.user.to_account_info(),
-                    to: referral_pool.to_account_info(),
-                },
-            ),
-            fees.referral,
-        )?;
+        require!(
+            ctx.accounts.referrer_account.is_none(),
+            StakingError::InvalidReferrer
+        );
+
         referral_pool.balance = safe_add(referral_pool.balance, fees.referral)?;
 
         None
     };
 
-    // 5. Transfer remaining funds (Net Stake + Stakers Fee) to Staking Pool Vault
-    // This is 9700 BPS (9000 Net + 700 Stakers Reward)
-    // We calculate this as gross_amount - distributed_external_fees
-    let distributed_external_fees = fees
-        .platform
-        .checked_add(fees.material_dart)
-        .ok_or(StakingError::MathOverflow)?
-        .checked_add(fees.bonus_pool)
-        .ok_or(StakingError::MathOverflow)?
-        .checked_add(fees.referral)
-        .ok_or(StakingError::MathOverflow)?;
-
-    let vault_amount = gross_amount
-        .checked_sub(distributed_external_fees)
-        .ok_or(StakingError::MathUnderflow)?;
-
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user.to_account_info(),
-                to: staking_pool.to_account_info(),
-            },
-        ),
-        vault_amount,
-    )?;
-
     // 5. Update reward_per_share with 700 BPS for all stakers
     if staking_pool.total_staked > 0 {
         update_reward_per_share(staking_pool, fees.stakers)?;
@@ -212,41 +305,60 @@ pub fn stake_handler(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
 
     // ========== BONUS POOL MECHANICS ==========
 
-    // Update last investment timestamp
-    bonus_pool.last_investment_timestamp = current_timestamp;
-
-    // If stake >= 1 SOL: extend countdown and update last-10
-    if gross_amount >= BONUS_EXTENSION_THRESHOLD {
-        // Extend countdown by 15 minutes
-        extend_bonus_countdown(bonus_pool)?;
+    // Expired rounds are frozen until distributed
+    let round_state = refresh_bonus_round_state(bonus_pool, current_timestamp);
+    let qualifying = gross_amount >= scale_to_decimals(BONUS_EXTENSION_THRESHOLD, decimals)?;
 
-        // Add to last-10 circular buffer
-        let position = add_to_last_ten(bonus_pool, user_key, gross_amount)?;
-
-        emit!(InvestorAddedToLastTen {
-            investor: user_key,
-            amount: gross_amount,
-            position,
-            timestamp: current_timestamp,
-        });
+    // First qualifying stake after a distribution starts the next round
+    let round_started = qualifying && round_state == BonusRoundState::Distributed;
+    if round_started {
+        start_bonus_round(bonus_pool, current_timestamp)?;
 
-        emit!(BonusCountdownExtended {
-            extended_by: BONUS_EXTENSION_SECONDS,
-            new_expiry: bonus_pool.expiry_timestamp,
+        emit!(BonusRoundStarted {
+            round: bonus_pool.current_round,
+            expiry_timestamp: bonus_pool.expiry_timestamp,
             staker: user_key,
-            stake_amount: gross_amount,
             timestamp: current_timestamp,
         });
     }
 
-    // ========== UPDATE USER STAKE ==========
+    if bonus_pool.round_state == BonusRoundState::Active {
+        // If stake >= 1 SOL: reset inactivity and extend countdown
+        if qualifying {
+            bonus_pool.last_investment_timestamp = current_timestamp;
+        }
+        if qualifying && !round_started {
+            // Extend countdown (15 minutes, or per whole SOL; capped)
+            let extended_by =
+                extend_bonus_countdown(bonus_pool, gross_amount, decimals, current_timestamp)?;
+
+            emit!(BonusCountdownExtended {
+                extended_by,
+                new_expiry: bonus_pool.expiry_timestamp,
+                staker: user_key,
+                stake_amount: gross_amount,
+                timestamp: current_timestamp,
+            });
+        }
+
+        // If stake >= last-10 threshold (1 SOL by default): update last-10
+        if gross_amount >= bonus_pool.config.last_ten_threshold {
+            // Add to last-10 circular buffer
+            // Entries hold the net stake (fee excluded) as their pro-rata weight
+            let (position, updated_existing) =
+                add_to_last_ten(bonus_pool, user_key, fees.net_amount, current_timestamp)?;
+
+            emit!(InvestorAddedToLastTen {
+                investor: user_key,
+                amount: fees.net_amount,
+                position,
+                updated_existing,
+                timestamp: current_timestamp,
+            });
+        }
+    }
 
-    // Calculate pending rewards if user already has a stake
-    let pending_rewards = if user_stake.staked_amount > 0 {
-        get_pending_rewards(user_stake, staking_pool)?
-    } else {
-        0
-    };
+    // ========== UPDATE USER STAKE ==========
 
     // Initialize user stake if first time
     if user_stake.user == Pubkey::default() {
@@ -258,18 +370,72 @@ pub fn stake_handler(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
         user_stake.last_claim_timestamp = current_timestamp;
         user_stake.referrer = referrer_pubkey;
         user_stake.bump = ctx.bumps.user_stake;
+        user_stake.last_unstake_timestamp = 0;
+        user_stake.lockup_tier = LockupTier::None;
+        user_stake.unlock_timestamp = 0;
+        user_stake.weighted_shares = 0;
+        user_stake.pending_unstake = 0;
+        user_stake.unstake_ready_timestamp = 0;
+        user_stake.reward_recipient = Pubkey::default();
+        user_stake.accrued_rewards = 0;
 
         // Increment global counters
         staking_pool.total_stakers = safe_add(staking_pool.total_stakers, 1)?;
         bonus_pool.total_participants = safe_add(bonus_pool.total_participants, 1)?;
     }
 
+    // Pending rewards stay claimable: carry them over before the weight changes
+    settle_rewards(user_stake, staking_pool)?;
+
+    // Average the deposit time over the position (unstake fee decay)
+    user_stake.stake_timestamp = calculate_weighted_timestamp(
+        user_stake.staked_amount,
+        user_stake.stake_timestamp,
+        fees.net_amount,
+        current_timestamp,
+    )?;
+
     // Add net amount (90%) to user's stake
     user_stake.staked_amount = safe_add(user_stake.staked_amount, fees.net_amount)?;
 
+    // Lock the position (optional) and reweight it by its lockup multiplier
+    apply_lockup(user_stake, lockup_tier, current_timestamp)?;
+    refresh_weighted_shares(user_stake, staking_pool, current_timestamp)?;
+
     // Update reward debt to prevent double claims
     user_stake.reward_debt =
-        calculate_reward_debt(user_stake.staked_amount, staking_pool.reward_per_share)?;
+        calculate_reward_debt(user_stake.weighted_shares, staking_pool.reward_per_share)?;
+
+    // Liquid pools: mint receipt shares at the share price after this stake's fee
+    let shares_minted = if staking_pool.is_liquid() {
+        let shares = calculate_shares_for_deposit(
+            fees.net_amount,
+            staking_pool.total_staked,
+            staking_pool.total_shares,
+        )?;
+        require!(shares > 0, StakingError::StakeTooSmall);
+
+        let receipt = receipt_token_accounts(
+            staking_pool,
+            &user_key,
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.user_receipt_account,
+            &ctx.accounts.receipt_token_program,
+        )?;
+        let pool_id = staking_pool.pool_id.to_le_bytes();
+        let bump = [staking_pool.bump];
+        mint_receipt_tokens(
+            &receipt,
+            staking_pool.to_account_info(),
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+            shares,
+        )?;
+
+        staking_pool.total_shares = safe_add(staking_pool.total_shares, shares)?;
+        shares
+    } else {
+        0
+    };
 
     // Update total staked in pool (net amount only)
     staking_pool.total_staked = safe_add(staking_pool.total_staked, fees.net_amount)?;
@@ -279,6 +445,8 @@ pub fn stake_handler(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
 
     emit!(Staked {
         user: user_key,
+        funder,
+        pool: staking_pool.key(),
         gross_amount,
         net_amount: fees.net_amount,
         fee_to_stakers: fees.stakers,
@@ -289,18 +457,204 @@ pub fn stake_handler(ctx: Context<Stake>, gross_amount: u64) -> Result<()> {
         referrer: referrer_pubkey,
         total_staked_after: staking_pool.total_staked,
         reward_per_share_after: staking_pool.reward_per_share,
+        shares_minted,
+        accrued_rewards: user_stake.accrued_rewards,
+        lockup_tier: user_stake.lockup_tier,
+        unlock_timestamp: user_stake.unlock_timestamp,
+        weighted_shares: user_stake.weighted_shares,
         timestamp: current_timestamp,
     });
 
     msg!(" ✅ Stake successful!");
     msg!("User: {}", user_key);
+    if funder != user_key {
+        msg!("Funded by: {}", funder);
+    }
     msg!("Gross: {} lamports", gross_amount);
     msg!("Net staked: {} lamports", fees.net_amount);
-    msg!("Total fee: {} lamports (10%)", fees.total_fee);
     msg!(
-        "Pending rewards auto-compounded: {} lamports",
-        pending_rewards
+        "Total fee: {} lamports ({} BPS)",
+        fees.total_fee,
+        staking_pool.fee_schedule.total_bps()
+    );
+    msg!(
+        "Unclaimed rewards carried over: {} lamports",
+        user_stake.accrued_rewards
     );
 
     Ok(())
 }
+
+impl<'info> Stake<'info> {
+    /// Owner of the position being staked into (beneficiary or the signer)
+    fn position_owner(&self) -> Pubkey {
+        self.beneficiary
+            .as_ref()
+            .map_or(self.user.key(), |beneficiary| beneficiary.key())
+    }
+
+    /// The position owner's current position before this stake
+    ///
+    /// Liquid pools value the staker's receipt balance at the share price
+    /// (receipts are transferable, so the per-user cap is per holder account).
+    fn current_position(&self) -> Result<u64> {
+        if !self.staking_pool.is_liquid() {
+            return Ok(self.user_stake.staked_amount);
+        }
+
+        let receipt = receipt_token_accounts(
+            &self.staking_pool,
+            &self.position_owner(),
+            &self.receipt_mint,
+            &self.user_receipt_account,
+            &self.receipt_token_program,
+        )?;
+        calculate_assets_for_shares(
+            receipt.token_account.amount,
+            self.staking_pool.total_staked,
+            self.staking_pool.total_shares,
+        )
+    }
+
+    /// Move a SOL stake: external fees to their recipients, the bonus and
+    /// referral shares to their pool accounts, the rest to the staking pool
+    fn transfer_stake_lamports(&self, fees: &FeeBreakdown, gross_amount: u64) -> Result<()> {
+        // 1. Transfer 100 BPS to treasury (platform commission)
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                },
+            ),
+            fees.platform,
+        )?;
+
+        // 2. Transfer 50 BPS to Material Dart team
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user.to_account_info(),
+                    to: self.material_dart_wallet.to_account_info(),
+                },
+            ),
+            fees.material_dart,
+        )?;
+
+        //  3. Add 100 BPS to bonus pool
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user.to_account_info(),
+                    to: self.bonus_pool.to_account_info(),
+                },
+            ),
+            fees.bonus_pool,
+        )?;
+
+        // 4. Referral (50 BPS): referrer directly, otherwise the referral pool
+        let referral_destination = match self.referrer {
+            Some(ref referrer) => referrer.to_account_info(),
+            None => self.referral_pool.to_account_info(),
+        };
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user.to_account_info(),
+                    to: referral_destination,
+                },
+            ),
+            fees.referral,
+        )?;
+
+        // 5. Transfer remaining funds (Net Stake + Stakers Fee) to Staking Pool Vault
+        // This is 9700 BPS (9000 Net + 700 Stakers Reward) by default
+        // We calculate this as gross_amount - distributed_external_fees
+        let distributed_external_fees = fees
+            .platform
+            .checked_add(fees.material_dart)
+            .ok_or(StakingError::MathOverflow)?
+            .checked_add(fees.bonus_pool)
+            .ok_or(StakingError::MathOverflow)?
+            .checked_add(fees.referral)
+            .ok_or(StakingError::MathOverflow)?;
+
+        let vault_amount = gross_amount
+            .checked_sub(distributed_external_fees)
+            .ok_or(StakingError::MathUnderflow)?;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.user.to_account_info(),
+                    to: self.staking_pool.to_account_info(),
+                },
+            ),
+            vault_amount,
+        )
+    }
+
+    /// Move a token stake: external fees to their token accounts, everything
+    /// else (stake, stakers fee, bonus and referral pool shares) to the vault
+    fn transfer_stake_tokens(&self, fees: &FeeBreakdown, gross_amount: u64) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let mint = self.staking_pool.mint;
+
+        let user_token_account =
+            validate_token_account(self.user_token_account.as_ref(), &mint, &self.user.key())?;
+        let treasury_token_account = validate_token_account(
+            self.treasury_token_account.as_ref(),
+            &mint,
+            &self.treasury.key(),
+        )?;
+        let material_dart_token_account = validate_token_account(
+            self.material_dart_token_account.as_ref(),
+            &mint,
+            &self.material_dart_wallet.key(),
+        )?;
+
+        let pay = |to: AccountInfo<'info>, amount: u64| {
+            transfer_pool_tokens(
+                &tokens,
+                user_token_account.to_account_info(),
+                to,
+                self.user.to_account_info(),
+                &[],
+                amount,
+            )
+        };
+
+        // 1. Platform commission and 2. Material Dart team
+        pay(treasury_token_account.to_account_info(), fees.platform)?;
+        pay(
+            material_dart_token_account.to_account_info(),
+            fees.material_dart,
+        )?;
+
+        // 3./4. Referrer is paid directly; the referral pool share stays in the vault
+        let mut external_fees = safe_add(fees.platform, fees.material_dart)?;
+        if let Some(ref referrer) = self.referrer {
+            let referrer_token_account = validate_token_account(
+                self.referrer_token_account.as_ref(),
+                &mint,
+                &referrer.key(),
+            )?;
+            pay(referrer_token_account.to_account_info(), fees.referral)?;
+            external_fees = safe_add(external_fees, fees.referral)?;
+        }
+
+        // 5. Everything else goes to the vault
+        let vault_amount = safe_sub(gross_amount, external_fees)?;
+        pay(tokens.vault.to_account_info(), vault_amount)
+    }
+}
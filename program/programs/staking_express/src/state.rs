@@ -4,6 +4,10 @@ use crate::constants::*;
 
 /// Global configuration for the Staking Express protocol
 /// PDA derived from ["global_config"]
+///
+/// Not layout-compatible with deployments that stored a single `paused` bool:
+/// a stored `true` would read back as STAKE only and the fields after it would
+/// shift. Such deployments need a fresh deploy (new state), not an in-place upgrade.
 #[account]
 pub struct GlobalConfig {
     /// Protocol authority (can update settings)
@@ -15,13 +19,24 @@ pub struct GlobalConfig {
     /// Material Dart team wallet (50 BPS)
     pub material_dart_wallet: Pubkey,
 
-    /// Whether the protocol is paused
-    pub paused: bool,
+    /// Paused operations (bitflags from `pause_flags`; replaces the former
+    /// `paused` bool)
+    pub pause_flags: u8,
+
+    /// Guardian (can pause operations only; Pubkey::default() = none)
+    pub guardian: Pubkey,
 
     /// Whether the protocol is initialized
     pub is_initialized: bool,
 
     pub bump: u8,
+
+    /// Number of staking pools created (next pool_id)
+    pub pool_count: u64,
+
+    /// Merkle root of wallets that may claim an AllowlistEntry in allowlist-gated
+    /// pools ([0; 32] = none)
+    pub allowlist_merkle_root: [u8; 32],
 }
 
 impl GlobalConfig {
@@ -29,23 +44,36 @@ impl GlobalConfig {
         32 + // authority
         32 + // treasury
         32 + // material_dart_wallet
-        1 +  // paused
+        1 +  // pause_flags
+        32 + // guardian
         1 +  // is_initialized
-        1; // bump
+        1 +  // bump
+        8 +  // pool_count
+        32; // allowlist_merkle_root
+
+    /// Whether `key` is the configured guardian
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        self.guardian != Pubkey::default() && self.guardian == *key
+    }
+
+    /// Whether any of the given operations is paused
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.pause_flags & flags != 0
+    }
 }
 
-/// Main staking pool state
-/// PDA derived from ["staking_pool"]
+/// Staking pool state (one per pool; shares GlobalConfig)
+/// PDA derived from ["staking_pool", pool_id.to_le_bytes()]
 #[account]
 pub struct StakingPool {
     /// Global config account
     pub config: Pubkey,
 
-    /// Total amount of SOL staked (net of fees)
+    /// Total amount staked (net of fees), in lamports or token base units
+    /// (liquid pools: total assets backing receipt shares, including accrued rewards)
     pub total_staked: u64,
 
-    /// Accumulated reward per share (scaled by REWARD_PRECISION = 1e12)
-    /// Formula: reward_per_share += (fee_amount * REWARD_PRECISION) / total_staked
+    /// Accumulated reward per weighted share (scaled by REWARD_PRECISION = 1e12)
     pub reward_per_share: u128,
 
     /// Last timestamp when pool was updated
@@ -55,6 +83,64 @@ pub struct StakingPool {
     pub total_stakers: u64,
 
     pub bump: u8,
+
+    /// Pool index (0 = pool created by initialize)
+    pub pool_id: u64,
+
+    /// Stake/unstake fee split for this pool
+    pub fee_schedule: FeeSchedule,
+
+    /// SPL token mint of a token pool (Pubkey::default() = native SOL)
+    pub mint: Pubkey,
+
+    /// Token vault of a token pool, owned by this PDA
+    /// (holds stakes, rewards and the bonus/referral pool balances)
+    pub vault: Pubkey,
+
+    /// Token program of the mint (Token or Token-2022)
+    pub token_program: Pubkey,
+
+    /// Receipt token mint of a liquid pool (Pubkey::default() = not liquid)
+    pub receipt_mint: Pubkey,
+
+    /// Receipt shares outstanding (liquid pools)
+    pub total_shares: u64,
+
+    /// Age-based decay of the unstake fee
+    pub unstake_fee_curve: UnstakeFeeCurve,
+
+    /// Delay between request_unstake and withdraw_unstaked, in seconds
+    /// (0 = instant unstake allowed)
+    pub unstake_cooldown_seconds: i64,
+
+    /// Net unstaked amounts queued for withdrawal (held by the pool, earning nothing)
+    pub total_pending_unstake: u64,
+
+    /// Sum of stakers' reward weights (staked_amount x lockup multiplier)
+    /// Formula: reward_per_share += (fee_amount * REWARD_PRECISION) / total_weighted_shares
+    pub total_weighted_shares: u64,
+
+    /// Share of total_staked to keep delegated to validators (SOL pools, BPS)
+    pub delegation_target_bps: u16,
+
+    /// Lamports held in validator stake accounts (principal plus harvested rewards)
+    pub total_delegated: u64,
+
+    /// Number of validator stake accounts created (next stake account index)
+    pub stake_account_count: u64,
+
+    /// Cap on total_staked accepted by stake (0 = no cap)
+    pub max_total_staked: u64,
+
+    /// Cap on a single position accepted by stake (0 = no cap)
+    pub max_stake_per_user: u64,
+
+    /// Whether stake requires an AllowlistEntry for the staker
+    pub allowlist_enabled: bool,
+
+    /// Decimals of the pool asset (SOL_DECIMALS for SOL pools)
+    /// SOL-denominated minimums and thresholds are scaled to these decimals
+    pub decimals: u8,
 }
 
 impl StakingPool {
@@ -64,7 +150,110 @@ impl StakingPool {
         16 + // reward_per_share (u128)
         8 +  // last_update_timestamp
         8 +  // total_stakers
-        1; // bump
+        1 +  // bump
+        8 +  // pool_id
+        FeeSchedule::LEN + // fee_schedule
+        32 + // mint
+        32 + // vault
+        32 + // token_program
+        32 + // receipt_mint
+        8 +  // total_shares
+        UnstakeFeeCurve::LEN + // unstake_fee_curve
+        8 +  // unstake_cooldown_seconds
+        8 +  // total_pending_unstake
+        8 +  // total_weighted_shares
+        2 +  // delegation_target_bps
+        8 +  // total_delegated
+        8 +  // stake_account_count
+        8 +  // max_total_staked
+        8 +  // max_stake_per_user
+        1 +  // allowlist_enabled
+        1; // decimals
+
+    /// Whether the pool is denominated in an SPL token rather than SOL
+    pub fn is_token_pool(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    /// Whether positions are represented by receipt tokens (share-price rewards)
+    pub fn is_liquid(&self) -> bool {
+        self.receipt_mint != Pubkey::default()
+    }
+}
+
+/// Per-pool fee split in basis points (applied to both stake and unstake)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSchedule {
+    /// Share distributed to stakers via reward_per_share
+    pub stakers_bps: u16,
+
+    /// Platform commission (treasury)
+    pub platform_bps: u16,
+
+    /// Share added to the pool's bonus pool
+    pub bonus_pool_bps: u16,
+
+    /// Referral commission (referrer or referral pool)
+    pub referral_bps: u16,
+
+    /// Material Dart team allocation
+    pub material_dart_bps: u16,
+}
+
+impl FeeSchedule {
+    pub const LEN: usize = 2 + // stakers_bps
+        2 +  // platform_bps
+        2 +  // bonus_pool_bps
+        2 +  // referral_bps
+        2; // material_dart_bps
+
+    /// Total fee in basis points
+    pub fn total_bps(&self) -> u64 {
+        self.stakers_bps as u64
+            + self.platform_bps as u64
+            + self.bonus_pool_bps as u64
+            + self.referral_bps as u64
+            + self.material_dart_bps as u64
+    }
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self {
+            stakers_bps: FEE_STAKERS_BPS as u16,
+            platform_bps: FEE_PLATFORM_BPS as u16,
+            bonus_pool_bps: FEE_BONUS_POOL_BPS as u16,
+            referral_bps: FEE_REFERRAL_BPS as u16,
+            material_dart_bps: FEE_MATERIAL_DART_BPS as u16,
+        }
+    }
+}
+
+/// Unstake fee that decays with the age of a position
+///
+/// The full schedule applies for `full_fee_seconds` after stake_timestamp, then
+/// the total fee decays linearly over `decay_seconds` to `floor_bps`; each
+/// component keeps its share of the total.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnstakeFeeCurve {
+    /// Whether the curve applies (disabled = full fee schedule on every unstake)
+    pub enabled: bool,
+
+    /// Position age with the full unstake fee, in seconds
+    pub full_fee_seconds: i64,
+
+    /// Decay period from the full fee to the floor, in seconds (0 = step to the floor)
+    pub decay_seconds: i64,
+
+    /// Total unstake fee after decay (BPS, capped at the schedule total)
+    pub floor_bps: u16,
+}
+
+impl UnstakeFeeCurve {
+    pub const LEN: usize = 1 + // enabled
+        8 +  // full_fee_seconds
+        8 +  // decay_seconds
+        2; // floor_bps
 }
 
 /// User's individual staking position
@@ -81,11 +270,11 @@ pub struct UserStakeState {
     pub staked_amount: u64,
 
     /// Reward debt for reward calculation
-    /// Formula: reward_debt = staked_amount * reward_per_share / REWARD_PRECISION
+    /// Formula: reward_debt = weighted_shares * reward_per_share / REWARD_PRECISION
     /// Prevents double-claiming when stake changes
     pub reward_debt: u128,
 
-    /// Timestamp when user first staked
+    /// Stake-weighted average deposit time (drives the unstake fee curve)
     pub stake_timestamp: i64,
 
     /// Last timestamp when user claimed rewards
@@ -95,6 +284,32 @@ pub struct UserStakeState {
     pub referrer: Option<Pubkey>,
 
     pub bump: u8,
+
+    /// Last timestamp when user unstaked (0 = never)
+    pub last_unstake_timestamp: i64,
+
+    /// Lockup tier of the position (reset to None once unlocked and touched
+    /// or refreshed)
+    pub lockup_tier: LockupTier,
+
+    /// Position cannot be unstaked before this timestamp (0 = not locked)
+    pub unlock_timestamp: i64,
+
+    /// Reward weight: staked_amount x lockup multiplier
+    pub weighted_shares: u64,
+
+    /// Net amount requested for unstake, withdrawable after the cooldown
+    pub pending_unstake: u64,
+
+    /// Timestamp when pending_unstake becomes withdrawable
+    pub unstake_ready_timestamp: i64,
+
+    /// Wallet that receives reward payouts (Pubkey::default() = the owner)
+    pub reward_recipient: Pubkey,
+
+    /// Rewards earned before the reward weight last changed, not yet paid out
+    /// (counted in pending rewards)
+    pub accrued_rewards: u64,
 }
 
 impl UserStakeState {
@@ -106,11 +321,67 @@ impl UserStakeState {
         8 +  // stake_timestamp
         8 +  // last_claim_timestamp
         33 + // referrer (Option<Pubkey>)
-        1; // bump
+        1 +  // bump
+        8 +  // last_unstake_timestamp
+        1 +  // lockup_tier
+        8 +  // unlock_timestamp
+        8 +  // weighted_shares
+        8 +  // pending_unstake
+        8 +  // unstake_ready_timestamp
+        32 + // reward_recipient
+        8; // accrued_rewards
+
+    /// Wallet the position's rewards are paid to
+    pub fn reward_destination(&self) -> Pubkey {
+        if self.reward_recipient == Pubkey::default() {
+            self.user
+        } else {
+            self.reward_recipient
+        }
+    }
+}
+
+/// Optional stake lockup; locked stake earns a boosted reward weight
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LockupTier {
+    /// No lockup (1x)
+    #[default]
+    None,
+
+    /// 30-day lockup (1.25x)
+    Days30,
+
+    /// 90-day lockup (1.5x)
+    Days90,
+
+    /// 180-day lockup (2x)
+    Days180,
+}
+
+impl LockupTier {
+    /// Lockup duration in seconds
+    pub fn duration(&self) -> i64 {
+        match self {
+            LockupTier::None => 0,
+            LockupTier::Days30 => LOCKUP_30_DAYS,
+            LockupTier::Days90 => LOCKUP_90_DAYS,
+            LockupTier::Days180 => LOCKUP_180_DAYS,
+        }
+    }
+
+    /// Reward weight multiplier in BPS (10000 = 1x)
+    pub fn multiplier_bps(&self) -> u64 {
+        match self {
+            LockupTier::None => LOCKUP_NONE_MULTIPLIER_BPS,
+            LockupTier::Days30 => LOCKUP_30_DAYS_MULTIPLIER_BPS,
+            LockupTier::Days90 => LOCKUP_90_DAYS_MULTIPLIER_BPS,
+            LockupTier::Days180 => LOCKUP_180_DAYS_MULTIPLIER_BPS,
+        }
+    }
 }
 
 /// Bonus pool with countdown mechanism
-/// PDA derived from ["bonus_pool"]
+/// PDA derived from ["bonus_pool", staking_pool]
 #[account]
 pub struct BonusPool {
     /// Staking pool this bonus pool belongs to
@@ -122,23 +393,56 @@ pub struct BonusPool {
     /// Countdown expiry timestamp (12 hours initially)
     pub expiry_timestamp: i64,
 
-    /// Last investment timestamp (for 6-hour inactivity check)
+    /// Last qualifying investment timestamp (for 6-hour inactivity check)
     pub last_investment_timestamp: i64,
 
     /// Circular buffer: last 10 investors
     /// Only updated when deposit >= 1 SOL
     pub last_ten_investors: [LastTenInvestor; MAX_LAST_TEN_INVESTORS],
 
-    /// Current position in circular buffer (0-9)
+    /// Current position in circular buffer (0..max_last_ten)
     pub current_position: u8,
 
-    /// Number of investors in buffer (0-10)
+    /// Number of investors in buffer (0..=max_last_ten)
     pub investor_count: u8,
 
     /// Total number of unique participants ever
     pub total_participants: u64,
 
     pub bump: u8,
+
+    /// Current round number (incremented on each distribution)
+    pub current_round: u64,
+
+    /// Timestamp when the current round started
+    pub round_start_timestamp: i64,
+
+    /// How last-10 entries carry over between rounds
+    pub last_ten_policy: LastTenPolicy,
+
+    /// Refresh a repeat investor's existing slot instead of taking a new one
+    pub dedupe_last_ten: bool,
+
+    /// Maximum remaining countdown after an extension, in seconds (0 = uncapped)
+    pub max_countdown_seconds: i64,
+
+    /// Extend by BONUS_EXTENSION_SECONDS per whole SOL instead of a flat extension
+    pub proportional_extension: bool,
+
+    /// Cap on a single proportional extension, in seconds
+    pub max_extension_seconds: i64,
+
+    /// Minimum time a last-10 entry must be held before it is paid (0 = disabled)
+    pub min_hold_seconds: i64,
+
+    /// Forfeit an entry's bonus share if the investor unstaked after entering
+    pub forfeit_on_unstake: bool,
+
+    /// Tunable distribution ratios, durations and thresholds
+    pub config: BonusConfig,
+
+    /// State of the current round
+    pub round_state: BonusRoundState,
 }
 
 impl BonusPool {
@@ -147,11 +451,111 @@ impl BonusPool {
         8 +   // balance
         8 +   // expiry_timestamp
         8 +   // last_investment_timestamp
-        (40 * MAX_LAST_TEN_INVESTORS) + // last_ten_investors array
+        (LastTenInvestor::LEN * MAX_LAST_TEN_INVESTORS) + // last_ten_investors array
         1 +   // current_position
         1 +   // investor_count
         8 +   // total_participants
-        1; // bump
+        1 +   // bump
+        8 +   // current_round
+        8 +   // round_start_timestamp
+        1 +   // last_ten_policy
+        1 +   // dedupe_last_ten
+        8 +   // max_countdown_seconds
+        1 +   // proportional_extension
+        8 +   // max_extension_seconds
+        8 +   // min_hold_seconds
+        1 +   // forfeit_on_unstake
+        BonusConfig::LEN + // config
+        1; // round_state
+}
+
+/// Lifecycle of a bonus round
+///
+/// Active → Expired: countdown or inactivity deadline passes (observed by stake/distribute)
+/// Expired → Distributed: distribute_bonus_pool pays out the round
+/// Distributed → Active: next qualifying stake (>= 1 SOL) starts a new countdown
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BonusRoundState {
+    /// Countdown running; qualifying stakes extend it and enter the last-10 list
+    #[default]
+    Active,
+
+    /// A deadline passed; the round is frozen until distributed
+    Expired,
+
+    /// Paid out; waiting for a qualifying stake to start the next round
+    Distributed,
+}
+
+/// Tunable bonus game parameters (updated by authority between seasons)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BonusConfig {
+    /// Share of each distribution paid to last-10 investors (BPS)
+    pub last_ten_bps: u16,
+
+    /// Share of each distribution paid to all stakers (BPS)
+    pub stakers_bps: u16,
+
+    /// Share of each distribution carried forward (BPS)
+    pub carry_forward_bps: u16,
+
+    /// Countdown duration after each distribution, in seconds
+    pub initial_countdown: i64,
+
+    /// Inactivity period that triggers a distribution, in seconds
+    pub inactivity_threshold: i64,
+
+    /// Minimum gross stake to enter the last-10 list (lamports)
+    pub last_ten_threshold: u64,
+
+    /// Active size of the last-10 list (1..=MAX_LAST_TEN_INVESTORS)
+    pub max_last_ten: u8,
+
+    /// Distribution mode
+    pub mode: BonusMode,
+
+    /// Jackpot for the last qualifying investor in LastInvestorJackpot mode (BPS)
+    pub jackpot_bps: u16,
+}
+
+impl BonusConfig {
+    pub const LEN: usize = 2 + // last_ten_bps
+        2 +  // stakers_bps
+        2 +  // carry_forward_bps
+        8 +  // initial_countdown
+        8 +  // inactivity_threshold
+        8 +  // last_ten_threshold
+        1 +  // max_last_ten
+        1 +  // mode
+        2; // jackpot_bps
+}
+
+impl Default for BonusConfig {
+    fn default() -> Self {
+        Self {
+            last_ten_bps: BONUS_LAST_TEN_BPS,
+            stakers_bps: BONUS_STAKERS_BPS,
+            carry_forward_bps: BONUS_CARRY_FORWARD_BPS,
+            initial_countdown: BONUS_INITIAL_COUNTDOWN,
+            inactivity_threshold: BONUS_INACTIVITY_THRESHOLD,
+            last_ten_threshold: BONUS_LAST_TEN_THRESHOLD,
+            max_last_ten: MAX_LAST_TEN_INVESTORS as u8,
+            mode: BonusMode::ProRata,
+            jackpot_bps: 0,
+        }
+    }
+}
+
+/// How a bonus round is paid out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BonusMode {
+    /// Ratios split between last-10 investors, all stakers and carry-forward
+    #[default]
+    ProRata,
+
+    /// On countdown expiry the most recent qualifying investor takes jackpot_bps,
+    /// the rest is split as in ProRata
+    LastInvestorJackpot,
 }
 
 /// Single entry in the last-10 circular buffer
@@ -160,11 +564,139 @@ pub struct LastTenInvestor {
     /// Investor's public key
     pub investor: Pubkey,
 
-    /// Amount staked (for pro-rata distribution)
+    /// Net amount staked (for pro-rata distribution)
     pub amount: u64,
+
+    /// Timestamp when the investor entered the list
+    pub timestamp: i64,
 }
+
+impl LastTenInvestor {
+    pub const LEN: usize = 32 + // investor
+        8 +  // amount
+        8; // timestamp
+}
+
+/// How the last-10 list carries over between bonus rounds
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LastTenPolicy {
+    /// Entries persist until pushed out by newer investors
+    #[default]
+    Persist,
+
+    /// List is cleared after each distribution
+    ClearOnDistribution,
+
+    /// Only entries made during the current round are paid
+    CurrentRoundOnly,
+}
+/// What triggered a bonus distribution
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BonusTrigger {
+    /// Countdown reached expiry_timestamp
+    Countdown,
+
+    /// No qualifying investment for config.inactivity_threshold
+    Inactivity,
+}
+
+/// Closed bonus round record
+/// PDA derived from ["bonus_round", bonus_pool, round.to_le_bytes()]
+#[account]
+pub struct BonusRound {
+    /// Round number (0 = first round after initialization)
+    pub round: u64,
+
+    /// Distribution trigger
+    pub trigger: BonusTrigger,
+
+    /// Last-10 snapshot at distribution with each investor's share
+    pub winners: [BonusRoundWinner; MAX_LAST_TEN_INVESTORS],
+
+    /// Number of valid entries in winners
+    pub winner_count: u8,
+
+    /// Bonus pool balance distributed this round
+    pub total_balance: u64,
+
+    /// Most recent qualifying investor awarded the jackpot (LastInvestorJackpot mode)
+    pub jackpot_winner: Option<Pubkey>,
+
+    /// Jackpot reserved for jackpot_winner (paid out by claim_jackpot)
+    pub jackpot: u64,
+
+    /// Slice for the last-10 investors (paid out by claim_last_ten_share)
+    pub to_last_ten: u64,
+
+    /// Slice for all stakers via reward_per_share
+    pub to_all_stakers: u64,
+
+    /// Amount carried forward to the next round
+    pub carried_forward: u64,
+
+    /// Timestamp when the round started
+    pub start_timestamp: i64,
+
+    /// Countdown expiry at distribution time
+    pub expiry_timestamp: i64,
+
+    /// Distribution timestamp
+    pub end_timestamp: i64,
+
+    pub bump: u8,
+
+    /// Whether jackpot_winner has claimed the jackpot
+    pub jackpot_claimed: bool,
+}
+
+impl BonusRound {
+    pub const LEN: usize = 8 +  // discriminator
+        8 +  // round
+        1 +  // trigger
+        (BonusRoundWinner::LEN * MAX_LAST_TEN_INVESTORS) + // winners array
+        1 +  // winner_count
+        8 +  // total_balance
+        33 + // jackpot_winner (Option<Pubkey>)
+        8 +  // jackpot
+        8 +  // to_last_ten
+        8 +  // to_all_stakers
+        8 +  // carried_forward
+        8 +  // start_timestamp
+        8 +  // expiry_timestamp
+        8 +  // end_timestamp
+        1 +  // bump
+        1; // jackpot_claimed
+}
+
+/// Single last-10 entry in a closed bonus round
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct BonusRoundWinner {
+    /// Investor's public key
+    pub investor: Pubkey,
+
+    /// Amount staked (pro-rata weight)
+    pub amount: u64,
+
+    /// Share of the last-10 slice
+    pub share: u64,
+
+    /// Share forfeited by anti-sniping rules (carried forward)
+    pub forfeited: bool,
+
+    /// Whether the investor has claimed the share
+    pub claimed: bool,
+}
+
+impl BonusRoundWinner {
+    pub const LEN: usize = 32 + // investor
+        8 +  // amount
+        8 +  // share
+        1 +  // forfeited
+        1; // claimed
+}
+
 /// Referral pool for accumulating referral fees
-/// PDA derived from ["referral_pool"]
+/// PDA derived from ["referral_pool", staking_pool]
 #[account]
 pub struct ReferralPool {
     /// Staking pool this referral pool belongs to
@@ -180,10 +712,32 @@ pub struct ReferralPool {
     /// Last distribution timestamp
     pub last_distribution_timestamp: i64,
 
-    /// Total distributed so far
+    /// Total distributed so far (stakers' half plus settled referrer shares)
     pub total_distributed: u64,
 
     pub bump: u8,
+
+    /// Share of each distribution paid to referrers pro-rata to referred volume
+    /// (BPS of the pool balance, taken from the carried-forward half; 0 = stakers only)
+    pub referrer_share_bps: u16,
+
+    /// Current referral period number (incremented on each distribution)
+    pub current_epoch: u64,
+
+    /// Gross stake volume brought in by registered referrers during the current period
+    pub epoch_referred_volume: u64,
+
+    /// Referrer allocation of the last closed period (claimable during the following period)
+    pub last_epoch_referrer_allocation: u64,
+
+    /// Referred volume of the last closed period
+    pub last_epoch_referred_volume: u64,
+
+    /// Portion of the last closed period's allocation already settled to referrers
+    pub last_epoch_referrer_settled: u64,
+
+    /// Settled referrer rewards not yet claimed (held in this account, excluded from balance)
+    pub referrer_claimable: u64,
 }
 
 impl ReferralPool {
@@ -193,11 +747,70 @@ impl ReferralPool {
         8 +  // next_distribution_timestamp
         8 +  // last_distribution_timestamp
         8 +  // total_distributed
+        1 +  // bump
+        2 +  // referrer_share_bps
+        8 +  // current_epoch
+        8 +  // epoch_referred_volume
+        8 +  // last_epoch_referrer_allocation
+        8 +  // last_epoch_referred_volume
+        8 +  // last_epoch_referrer_settled
+        8; // referrer_claimable
+}
+
+/// Closed referral period record
+/// PDA derived from ["referral_epoch", referral_pool, epoch.to_le_bytes()]
+#[account]
+pub struct ReferralEpoch {
+    /// Period number (0 = first period after initialization)
+    pub epoch: u64,
+
+    /// Period start (previous distribution or initialization)
+    pub start_timestamp: i64,
+
+    /// Period end (distribution timestamp)
+    pub end_timestamp: i64,
+
+    /// Pool balance distributed this period (includes swept referrer allocation)
+    pub balance_in: u64,
+
+    /// Unsettled referrer allocation from the previous period swept back in
+    pub swept_from_referrers: u64,
+
+    /// Amount distributed to all stakers via reward_per_share
+    pub to_stakers: u64,
+
+    /// Amount reserved for referrers pro-rata to referred volume
+    pub to_referrers: u64,
+
+    /// Amount carried forward to the next period
+    pub carried_forward: u64,
+
+    /// Total staked at distribution time (stakers' base)
+    pub total_staked: u64,
+
+    /// Gross volume brought in by registered referrers this period
+    pub referred_volume: u64,
+
+    pub bump: u8,
+}
+
+impl ReferralEpoch {
+    pub const LEN: usize = 8 +  // discriminator
+        8 +  // epoch
+        8 +  // start_timestamp
+        8 +  // end_timestamp
+        8 +  // balance_in
+        8 +  // swept_from_referrers
+        8 +  // to_stakers
+        8 +  // to_referrers
+        8 +  // carried_forward
+        8 +  // total_staked
+        8 +  // referred_volume
         1; // bump
 }
 
 /// Individual referrer tracking account
-/// PDA derived from ["referrer", referrer_pubkey]
+/// PDA derived from ["referrer", referral_pool, referrer_pubkey]
 #[account]
 pub struct ReferrerAccount {
     /// Referrer's public key
@@ -213,6 +826,21 @@ pub struct ReferrerAccount {
     pub created_at: i64,
 
     pub bump: u8,
+
+    /// Referral period the tracked volume belongs to
+    pub volume_epoch: u64,
+
+    /// Gross stake volume referred during `volume_epoch`
+    pub epoch_volume: u64,
+
+    /// Total gross stake volume referred across all periods
+    pub total_volume: u64,
+
+    /// Settled referral pool rewards available to claim
+    pub claimable: u64,
+
+    /// Referral pool this referrer is registered with
+    pub referral_pool: Pubkey,
 }
 
 impl ReferrerAccount {
@@ -221,9 +849,99 @@ impl ReferrerAccount {
         4 +  // referral_count
         8 +  // total_earned
         8 +  // created_at
+        1 +  // bump
+        8 +  // volume_epoch
+        8 +  // epoch_volume
+        8 +  // total_volume
+        8 +  // claimable
+        32; // referral_pool
+}
+
+/// Native stake account delegated by a SOL staking pool
+/// PDA derived from ["validator_stake", staking_pool, index.to_le_bytes()]
+#[account]
+pub struct ValidatorStake {
+    /// Staking pool that owns the stake account (staker and withdrawer authority)
+    pub staking_pool: Pubkey,
+
+    /// Stake account (PDA derived from ["stake_account", staking_pool, index.to_le_bytes()])
+    pub stake_account: Pubkey,
+
+    /// Validator vote account the stake is delegated to
+    pub vote_account: Pubkey,
+
+    /// Stake account index within the pool
+    pub index: u64,
+
+    /// Lamports counted in the pool's total_delegated (principal plus harvested rewards)
+    pub delegated: u64,
+
+    /// Rent-exempt reserve funded by the authority (returned on withdrawal)
+    pub rent_reserve: u64,
+
+    /// Whether the stake has been deactivated (withdrawable once cooled down)
+    pub deactivated: bool,
+
+    /// Timestamp when the stake was delegated
+    pub created_at: i64,
+
+    pub bump: u8,
+
+    /// Bump of the stake account PDA
+    pub stake_account_bump: u8,
+}
+
+impl ValidatorStake {
+    pub const LEN: usize = 8 +  // discriminator
+        32 + // staking_pool
+        32 + // stake_account
+        32 + // vote_account
+        8 +  // index
+        8 +  // delegated
+        8 +  // rent_reserve
+        1 +  // deactivated
+        8 +  // created_at
+        1 +  // bump
+        1; // stake_account_bump
+}
+
+/// Membership of a user in an allowlist-gated staking pool
+/// PDA derived from ["allowlist", staking_pool, user]
+///
+/// Added by the authority, or claimed by the user with a Merkle proof against
+/// GlobalConfig.allowlist_merkle_root
+#[account]
+pub struct AllowlistEntry {
+    /// Staking pool the entry belongs to
+    pub staking_pool: Pubkey,
+
+    /// Allowlisted user
+    pub user: Pubkey,
+
+    /// Timestamp when the user was allowlisted
+    pub created_at: i64,
+
+    pub bump: u8,
+}
+
+impl AllowlistEntry {
+    pub const LEN: usize = 8 +  // discriminator
+        32 + // staking_pool
+        32 + // user
+        8 +  // created_at
         1; // bump
 }
 
+/// Operations that can be paused independently (GlobalConfig.pause_flags)
+pub mod pause_flags {
+    pub const STAKE: u8 = 1 << 0;
+    pub const UNSTAKE: u8 = 1 << 1;
+    pub const CLAIM: u8 = 1 << 2;
+    pub const BONUS_DISTRIBUTION: u8 = 1 << 3;
+    pub const REFERRAL_DISTRIBUTION: u8 = 1 << 4;
+    pub const ALL: u8 = STAKE | UNSTAKE | CLAIM | BONUS_DISTRIBUTION | REFERRAL_DISTRIBUTION;
+}
+
 /// PDA seeds for deterministic address derivation
 pub mod seeds {
     pub const GLOBAL_CONFIG: &[u8] = b"global_config";
@@ -232,4 +950,11 @@ pub mod seeds {
     pub const BONUS_POOL: &[u8] = b"bonus_pool";
     pub const REFERRAL_POOL: &[u8] = b"referral_pool";
     pub const REFERRER: &[u8] = b"referrer";
+    pub const REFERRAL_EPOCH: &[u8] = b"referral_epoch";
+    pub const BONUS_ROUND: &[u8] = b"bonus_round";
+    pub const VAULT: &[u8] = b"vault";
+    pub const RECEIPT_MINT: &[u8] = b"receipt_mint";
+    pub const VALIDATOR_STAKE: &[u8] = b"validator_stake";
+    pub const STAKE_ACCOUNT: &[u8] = b"stake_account";
+    pub const ALLOWLIST: &[u8] = b"allowlist";
 }
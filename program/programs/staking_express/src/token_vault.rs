@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_interface::{
+    burn, mint_to, transfer_checked, Burn, Mint, MintTo, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+use crate::errors::StakingError;
+use crate::state::*;
+
+/// Token accounts shared by every token pool transfer
+pub struct PoolTokenAccounts<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub vault: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Resolve the optional token accounts of an instruction for a token pool
+///
+/// All three must be present and match the pool's mint, vault and token program
+pub fn pool_token_accounts<'a, 'info>(
+    staking_pool: &StakingPool,
+    mint: &'a Option<InterfaceAccount<'info, Mint>>,
+    vault: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    token_program: &'a Option<Interface<'info, TokenInterface>>,
+) -> Result<PoolTokenAccounts<'a, 'info>> {
+    let (Some(mint), Some(vault), Some(token_program)) =
+        (mint.as_ref(), vault.as_ref(), token_program.as_ref())
+    else {
+        return Err(StakingError::InvalidTokenAccount.into());
+    };
+
+    require!(
+        mint.key() == staking_pool.mint
+            && vault.key() == staking_pool.vault
+            && token_program.key() == staking_pool.token_program,
+        StakingError::InvalidTokenAccount
+    );
+
+    Ok(PoolTokenAccounts {
+        mint,
+        vault,
+        token_program,
+    })
+}
+
+/// Receipt token accounts of a liquid pool instruction
+pub struct ReceiptTokenAccounts<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Resolve the optional receipt token accounts of an instruction for a liquid pool
+///
+/// The mint must be the pool's receipt mint and the token account must be
+/// held by `owner`
+pub fn receipt_token_accounts<'a, 'info>(
+    staking_pool: &StakingPool,
+    owner: &Pubkey,
+    mint: &'a Option<InterfaceAccount<'info, Mint>>,
+    token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    token_program: &'a Option<Interface<'info, TokenInterface>>,
+) -> Result<ReceiptTokenAccounts<'a, 'info>> {
+    let (Some(mint), Some(token_program)) = (mint.as_ref(), token_program.as_ref()) else {
+        return Err(StakingError::InvalidTokenAccount.into());
+    };
+
+    require!(
+        mint.key() == staking_pool.receipt_mint
+            && *mint.to_account_info().owner == token_program.key(),
+        StakingError::InvalidTokenAccount
+    );
+    let token_account = validate_token_account(token_account.as_ref(), &mint.key(), owner)?;
+
+    Ok(ReceiptTokenAccounts {
+        mint,
+        token_account,
+        token_program,
+    })
+}
+
+/// Require a token account of the pool's mint owned by `owner`
+pub fn validate_token_account<'a, 'info>(
+    token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<&'a InterfaceAccount<'info, TokenAccount>> {
+    let token_account = token_account.ok_or(StakingError::InvalidTokenAccount)?;
+    require!(
+        token_account.mint == *mint && token_account.owner == *owner,
+        StakingError::InvalidTokenAccount
+    );
+
+    Ok(token_account)
+}
+
+/// Resolve the token account a position's rewards are paid to
+///
+/// The owner's token account, or the reward recipient's if one is set
+pub fn reward_token_account<'a, 'info>(
+    user_stake: &UserStakeState,
+    mint: &Pubkey,
+    user_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    reward_recipient_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+) -> Result<&'a InterfaceAccount<'info, TokenAccount>> {
+    if user_stake.reward_recipient == Pubkey::default() {
+        validate_token_account(user_token_account, mint, &user_stake.user)
+    } else {
+        validate_token_account(
+            reward_recipient_token_account,
+            mint,
+            &user_stake.reward_recipient,
+        )
+    }
+}
+
+/// Reject Token-2022 mints whose transfers would not deliver the full amount
+///
+/// Transfer fees, transfer hooks and non-transferable mints break the pool's
+/// balance accounting. A permanent delegate can drain the vault, and pausable
+/// or default-frozen mints can block withdrawals.
+pub fn validate_pool_mint(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let unsupported = mint_state.get_extension_types()?.iter().any(|extension| {
+        matches!(
+            extension,
+            ExtensionType::TransferFeeConfig
+                | ExtensionType::TransferHook
+                | ExtensionType::NonTransferable
+                | ExtensionType::PermanentDelegate
+                | ExtensionType::Pausable
+                | ExtensionType::DefaultAccountState
+        )
+    });
+    require!(!unsupported, StakingError::UnsupportedMint);
+
+    Ok(())
+}
+
+/// Transfer pool tokens with `transfer_checked`
+///
+/// `signer_seeds` is empty for user-signed transfers and holds the staking
+/// pool seeds for transfers out of the vault. Zero amounts are skipped.
+pub fn transfer_pool_tokens<'info>(
+    accounts: &PoolTokenAccounts<'_, 'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from,
+                mint: accounts.mint.to_account_info(),
+                to,
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        accounts.mint.decimals,
+    )
+}
+
+/// Mint receipt shares to a holder (signed by the staking pool PDA)
+pub fn mint_receipt_tokens<'info>(
+    accounts: &ReceiptTokenAccounts<'_, 'info>,
+    staking_pool: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintTo {
+                mint: accounts.mint.to_account_info(),
+                to: accounts.token_account.to_account_info(),
+                authority: staking_pool,
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Burn receipt shares from a holder's token account (signed by the holder)
+pub fn burn_receipt_tokens<'info>(
+    accounts: &ReceiptTokenAccounts<'_, 'info>,
+    holder: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    burn(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint: accounts.mint.to_account_info(),
+                from: accounts.token_account.to_account_info(),
+                authority: holder,
+            },
+        ),
+        amount,
+    )
+}
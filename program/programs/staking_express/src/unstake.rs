@@ -5,7 +5,9 @@ use crate::fees::*;
 use crate::helpers::*;
 use crate::math::*;
 use crate::state::*;
+use crate::token_vault::*;
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct Unstake<'info> {
@@ -20,33 +22,34 @@ pub struct Unstake<'info> {
         seeds = [seeds::GLOBAL_CONFIG],
         bump = global_config.bump,
         owner = crate::ID,
-        constraint = !global_config.paused @ StakingError::PoolPaused
+        constraint = !global_config.is_paused(pause_flags::UNSTAKE) @ StakingError::PoolPaused
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
     /// Staking pool
     #[account(
         mut,
-        seeds = [seeds::STAKING_POOL],
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
         bump = staking_pool.bump,
         owner = crate::ID
     )]
     pub staking_pool: Account<'info, StakingPool>,
 
-    /// User's stake state
+    /// User's stake state (created for holders of transferred receipt tokens)
     #[account(
-        mut,
+        init_if_needed,
+        payer = user,
+        space = UserStakeState::LEN,
         seeds = [seeds::USER_STAKE, user.key().as_ref(), staking_pool.key().as_ref()],
-        bump = user_stake.bump,
-        owner = crate::ID,
-        constraint = user_stake.user == user.key() @ StakingError::Unauthorized
+        bump,
+        constraint = user_stake.user == user.key() || user_stake.user == Pubkey::default() @ StakingError::Unauthorized
     )]
     pub user_stake: Account<'info, UserStakeState>,
 
     /// Bonus pool (receives 100 BPS)
     #[account(
         mut,
-        seeds = [seeds::BONUS_POOL],
+        seeds = [seeds::BONUS_POOL, staking_pool.key().as_ref()],
         bump = bonus_pool.bump,
         owner = crate::ID
     )]
@@ -55,20 +58,12 @@ pub struct Unstake<'info> {
     /// Referral pool (receives 50 BPS if no referrer)
     #[account(
         mut,
-        seeds = [seeds::REFERRAL_POOL],
+        seeds = [seeds::REFERRAL_POOL, staking_pool.key().as_ref()],
         bump = referral_pool.bump,
         owner = crate::ID
     )]
     pub referral_pool: Account<'info, ReferralPool>,
 
-    /// Staking pool account (PDA, holds staker funds)
-    #[account(
-        mut,
-        seeds = [seeds::STAKING_POOL],
-        bump = staking_pool.bump
-    )]
-    pub pool_vault: Account<'info, StakingPool>,
-
     /// CHECK: Validated against global_config and owner checked
     #[account(
         mut,
@@ -93,140 +88,277 @@ pub struct Unstake<'info> {
     )]
     pub referrer: Option<UncheckedAccount<'info>>,
 
+    /// Reward recipient (required if the position has one; receives SOL rewards)
+    /// CHECK: Validated against user_stake.reward_recipient and owner checked
+    #[account(
+        mut,
+        owner = anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub reward_recipient: Option<UncheckedAccount<'info>>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: user's token account (receives the unstake)
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: reward recipient's token account (receives the rewards)
+    #[account(mut)]
+    pub reward_recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: treasury's token account
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Material Dart team's token account
+    #[account(mut)]
+    pub material_dart_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: referrer's token account (if referred)
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Liquid pools: receipt token mint
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Liquid pools: user's receipt token account
+    #[account(mut)]
+    pub user_receipt_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Liquid pools: token program of the receipt mint
+    pub receipt_token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn unstake_handler(ctx: Context<Unstake>, gross_unstake_amount: u64) -> Result<()> {
-    // Validate minimum unstake
+/// `amount` is the gross amount to unstake, or the receipt shares to redeem in liquid pools
+pub fn unstake_handler(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    // Pools with a cooldown only exit through the withdrawal queue
     require!(
-        gross_unstake_amount >= MIN_UNSTAKE,
-        StakingError::UnstakeTooSmall
+        ctx.accounts.staking_pool.unstake_cooldown_seconds == 0,
+        StakingError::CooldownRequired
     );
 
+    process_unstake(ctx, amount, false)
+}
+
+/// Unstake into the withdrawal queue: fees and pending rewards are settled now,
+/// the net amount stops earning and is withdrawable after the pool's cooldown
+pub fn request_unstake_handler(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    process_unstake(ctx, amount, true)
+}
+
+/// Shared unstake flow; `queued` holds the net amount back for withdraw_unstaked
+fn process_unstake(ctx: Context<Unstake>, amount: u64, queued: bool) -> Result<()> {
     let user_key = ctx.accounts.user.key();
-    let staking_pool = &mut ctx.accounts.staking_pool;
-    let user_stake = &mut ctx.accounts.user_stake;
-    let bonus_pool = &mut ctx.accounts.bonus_pool;
-    let referral_pool = &mut ctx.accounts.referral_pool;
+    let liquid = ctx.accounts.staking_pool.is_liquid();
     let current_timestamp = get_current_timestamp()?;
 
-    // Validate user has sufficient staked balance
+    // Liquid pools redeem receipt shares at the current share price
+    let (gross_unstake_amount, shares_burned) = if liquid {
+        let staking_pool = &ctx.accounts.staking_pool;
+        let assets = calculate_assets_for_shares(
+            amount,
+            staking_pool.total_staked,
+            staking_pool.total_shares,
+        )?;
+        (assets, amount)
+    } else {
+        (amount, 0)
+    };
+
+    // Validate minimum unstake
     require!(
-        user_stake.staked_amount >= gross_unstake_amount,
-        StakingError::InsufficientStake
+        gross_unstake_amount >= scale_to_decimals(MIN_UNSTAKE, ctx.accounts.staking_pool.decimals)?,
+        StakingError::UnstakeTooSmall
     );
 
-    // ========== CALCULATE PENDING REWARDS (NO FEE) ==========
-    let pending_rewards = get_pending_rewards(user_stake, staking_pool)?;
+    // Validate user has sufficient staked balance (liquid pools: the receipt burn does)
+    if !liquid {
+        require!(
+            ctx.accounts.user_stake.staked_amount >= gross_unstake_amount,
+            StakingError::InsufficientStake
+        );
+    }
 
-    // ========== CALCULATE UNSTAKE FEE (10%) ==========
-    let fees = calculate_unstake_fee(gross_unstake_amount)?;
+    // Locked positions cannot be unstaked before their unlock timestamp
+    require!(
+        ctx.accounts.user_stake.unlock_timestamp <= current_timestamp,
+        StakingError::StakeLocked
+    );
+
+    // Holders of transferred receipt tokens get a fresh position record
+    if ctx.accounts.user_stake.user == Pubkey::default() {
+        require!(liquid, StakingError::NoStakePosition);
+
+        let staking_pool_key = ctx.accounts.staking_pool.key();
+        let user_stake = &mut ctx.accounts.user_stake;
+        user_stake.user = user_key;
+        user_stake.pool = staking_pool_key;
+        user_stake.referrer = None;
+        user_stake.stake_timestamp = current_timestamp;
+        user_stake.bump = ctx.bumps.user_stake;
+        user_stake.reward_recipient = Pubkey::default();
+    }
+
+    // ========== CALCULATE PENDING REWARDS (NO FEE) ==========
+    // Liquid pools pay rewards through the share price
+    let pending_rewards = if liquid {
+        0
+    } else {
+        get_pending_rewards(&ctx.accounts.user_stake, &ctx.accounts.staking_pool)?
+    };
+
+    // ========== CALCULATE UNSTAKE FEE (10% BY DEFAULT) ==========
+    // Decays with the position's age if the pool has a fee curve; receipt tokens
+    // change hands, so liquid pools always charge the full schedule
+    let fee_schedule = if liquid {
+        ctx.accounts.staking_pool.fee_schedule
+    } else {
+        let stake_age = current_timestamp.saturating_sub(ctx.accounts.user_stake.stake_timestamp);
+        unstake_fee_schedule(
+            &ctx.accounts.staking_pool.fee_schedule,
+            &ctx.accounts.staking_pool.unstake_fee_curve,
+            stake_age,
+        )?
+    };
+    let fees = calculate_unstake_fee(gross_unstake_amount, &fee_schedule)?;
     verify_fee_breakdown(&fees)?;
 
-    // ========== FEE DISTRIBUTION (FROM UNSTAKE AMOUNT) ==========
+    // Referred users must pass their referrer (paid directly); unreferred
+    // positions pay the referral pool and may not pass one
+    if let Some(referrer_pubkey) = ctx.accounts.user_stake.referrer {
+        let referrer = ctx
+            .accounts
+            .referrer
+            .as_ref()
+            .ok_or(StakingError::InvalidAmount)?;
+        require!(
+            referrer.key() == referrer_pubkey,
+            StakingError::InvalidAccountOwner
+        );
+    } else {
+        require!(
+            ctx.accounts.referrer.is_none() && ctx.accounts.referrer_token_account.is_none(),
+            StakingError::InvalidReferrer
+        );
+    }
 
-    // Fees are deducted from the unstaked amount
-    // User receives: pending_rewards (no fee) + net_unstake_amount (90%)
-
-    // 1. Transfer 100 BPS to treasury
-    **staking_pool.to_account_info().try_borrow_mut_lamports()? -= fees.platform;
-    **ctx
-        .accounts
-        .treasury
-        .to_account_info()
-        .try_borrow_mut_lamports()? += fees.platform;
-
-    // 2. Transfer 50 BPS to Material Dart
-    **staking_pool.to_account_info().try_borrow_mut_lamports()? -= fees.material_dart;
-    **ctx
-        .accounts
-        .material_dart_wallet
-        .to_account_info()
-        .try_borrow_mut_lamports()? += fees.material_dart;
-
-    // 3. Transfer 100 BPS to bonus pool
-    // Note: Bonus Pool is also a PDA owned by this program.
-    // We can just move lamports directly.
-    **staking_pool.to_account_info().try_borrow_mut_lamports()? -= fees.bonus_pool;
-    **bonus_pool.to_account_info().try_borrow_mut_lamports()? += fees.bonus_pool;
+    // ========== BURN RECEIPT SHARES (LIQUID POOLS) ==========
+
+    if liquid {
+        let receipt = receipt_token_accounts(
+            &ctx.accounts.staking_pool,
+            &user_key,
+            &ctx.accounts.receipt_mint,
+            &ctx.accounts.user_receipt_account,
+            &ctx.accounts.receipt_token_program,
+        )?;
+        burn_receipt_tokens(&receipt, ctx.accounts.user.to_account_info(), shares_burned)?;
+    }
 
-    bonus_pool.balance = safe_add(bonus_pool.balance, fees.bonus_pool)?;
+    // ========== FEE DISTRIBUTION AND TRANSFER TO USER ==========
 
-    // 4. Handle referral (50 BPS)
-    if let Some(referrer_pubkey) = user_stake.referrer {
-        if let Some(ref referrer_account) = ctx.accounts.referrer {
-            require!(
-                referrer_account.key() == referrer_pubkey,
-                StakingError::InvalidAccountOwner
-            );
-
-            // Pay referrer directly
-            **staking_pool.to_account_info().try_borrow_mut_lamports()? -= fees.referral;
-            **referrer_account
-                .to_account_info()
-                .try_borrow_mut_lamports()? += fees.referral;
-        } else {
-            return Err(StakingError::InvalidAmount.into());
-        }
+    // Fees are deducted from the unstaked amount
+    // User receives net_unstake_amount (90% by default) unless it is queued;
+    // pending_rewards (no fee) go to the user or their reward recipient
+    let principal_amount = if queued { 0 } else { fees.net_amount };
+    let reward_recipient = ctx.accounts.user_stake.reward_destination();
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts
+            .transfer_unstake_tokens(&fees, pending_rewards, principal_amount)?;
     } else {
-        // No referrer - add to referral pool
-        // Referral Pool is likely a PDA owned by this program too.
-        **staking_pool.to_account_info().try_borrow_mut_lamports()? -= fees.referral;
-        **referral_pool.to_account_info().try_borrow_mut_lamports()? += fees.referral;
+        ctx.accounts
+            .transfer_unstake_lamports(&fees, pending_rewards, principal_amount)?;
+    }
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let bonus_pool = &mut ctx.accounts.bonus_pool;
+    let referral_pool = &mut ctx.accounts.referral_pool;
 
+    // 100 BPS to bonus pool
+    bonus_pool.balance = safe_add(bonus_pool.balance, fees.bonus_pool)?;
+
+    // 50 BPS to referral pool if the user has no referrer
+    if user_stake.referrer.is_none() {
         referral_pool.balance = safe_add(referral_pool.balance, fees.referral)?;
     }
 
-    // 5. Update reward_per_share with 700 BPS for remaining stakers
+    // 700 BPS to remaining stakers
     if staking_pool.total_staked > gross_unstake_amount {
         update_reward_per_share(staking_pool, fees.stakers)?;
     }
 
-    // ========== TRANSFER TO USER ==========
-
-    // Transfer pending rewards (NO FEE)
-    if pending_rewards > 0 {
-        **staking_pool.to_account_info().try_borrow_mut_lamports()? -= pending_rewards;
-        **ctx
-            .accounts
-            .user
-            .to_account_info()
-            .try_borrow_mut_lamports()? += pending_rewards;
-    }
-
-    // Transfer net unstake amount (90%)
-    **staking_pool.to_account_info().try_borrow_mut_lamports()? -= fees.net_amount;
-    **ctx
-        .accounts
-        .user
-        .to_account_info()
-        .try_borrow_mut_lamports()? += fees.net_amount;
-
     // ========== UPDATE STATE ==========
 
-    // Update user's staked amount
-    user_stake.staked_amount = safe_sub(user_stake.staked_amount, gross_unstake_amount)?;
+    // Update user's staked amount (liquid pools: receipt tokens may have changed hands)
+    user_stake.staked_amount = if liquid {
+        user_stake
+            .staked_amount
+            .saturating_sub(gross_unstake_amount)
+    } else {
+        safe_sub(user_stake.staked_amount, gross_unstake_amount)?
+    };
 
-    // Update reward debt
+    // Reweight the position (its lockup has expired) and update reward debt
+    user_stake.accrued_rewards = 0;
+    refresh_weighted_shares(user_stake, staking_pool, current_timestamp)?;
     user_stake.reward_debt =
-        calculate_reward_debt(user_stake.staked_amount, staking_pool.reward_per_share)?;
+        calculate_reward_debt(user_stake.weighted_shares, staking_pool.reward_per_share)?;
+
+    // Record unstake time (anti-sniping forfeiture of bonus shares)
+    user_stake.last_unstake_timestamp = current_timestamp;
 
     // Update pool's total staked
     staking_pool.total_staked = safe_sub(staking_pool.total_staked, gross_unstake_amount)?;
+    staking_pool.total_shares = safe_sub(staking_pool.total_shares, shares_burned)?;
     staking_pool.last_update_timestamp = current_timestamp;
 
+    // Queue the net amount; a new request restarts the cooldown for the whole queue
+    if queued {
+        user_stake.pending_unstake = safe_add(user_stake.pending_unstake, fees.net_amount)?;
+        user_stake.unstake_ready_timestamp = current_timestamp
+            .checked_add(staking_pool.unstake_cooldown_seconds)
+            .ok_or(StakingError::MathOverflow)?;
+        staking_pool.total_pending_unstake =
+            safe_add(staking_pool.total_pending_unstake, fees.net_amount)?;
+
+        emit!(UnstakeRequested {
+            user: user_key,
+            pool: staking_pool.key(),
+            amount: fees.net_amount,
+            pending_total: user_stake.pending_unstake,
+            ready_timestamp: user_stake.unstake_ready_timestamp,
+            timestamp: current_timestamp,
+        });
+    }
+
     // ========== EMIT EVENT ==========
 
     emit!(Unstaked {
         user: user_key,
+        pool: staking_pool.key(),
         gross_amount: gross_unstake_amount,
         net_amount: fees.net_amount,
         rewards_claimed: pending_rewards,
+        reward_recipient,
+        shares_burned,
         fee_to_stakers: fees.stakers,
         fee_to_platform: fees.platform,
         fee_to_bonus: fees.bonus_pool,
         fee_to_referral: fees.referral,
         fee_to_material_dart: fees.material_dart,
+        fee_bps: fee_schedule.total_bps(),
         total_staked_after: staking_pool.total_staked,
         timestamp: current_timestamp,
     });
@@ -235,8 +367,296 @@ pub fn unstake_handler(ctx: Context<Unstake>, gross_unstake_amount: u64) -> Resu
     msg!("User: {}", user_key);
     msg!("Unstaked: {} lamports", gross_unstake_amount);
     msg!("Rewards claimed: {} lamports (NO FEE)", pending_rewards);
-    msg!("Net received: {} lamports (90%)", fees.net_amount);
-    msg!("Unstake fee: {} lamports (10%)", fees.total_fee);
+    if queued {
+        msg!(
+            "Net queued: {} lamports (withdrawable at {})",
+            fees.net_amount,
+            user_stake.unstake_ready_timestamp
+        );
+    } else {
+        msg!("Net received: {} lamports", fees.net_amount);
+    }
+    msg!(
+        "Unstake fee: {} lamports ({} BPS)",
+        fees.total_fee,
+        fee_schedule.total_bps()
+    );
 
     Ok(())
 }
+
+impl<'info> Unstake<'info> {
+    /// Pay out a SOL unstake from the staking pool account
+    fn transfer_unstake_lamports(
+        &self,
+        fees: &FeeBreakdown,
+        rewards: u64,
+        principal: u64,
+    ) -> Result<()> {
+        let staking_pool = self.staking_pool.to_account_info();
+
+        // Everything but the stakers' share (and a queued net amount) leaves the
+        // pool account; delegated lamports are not available until withdrawn
+        // from their stake accounts
+        let outflow = safe_add(
+            safe_sub(fees.total_fee, fees.stakers)?,
+            safe_add(rewards, principal)?,
+        )?;
+        require_pool_liquidity(&self.staking_pool, outflow)?;
+
+        // 1. Transfer 100 BPS to treasury
+        **staking_pool.try_borrow_mut_lamports()? -= fees.platform;
+        **self.treasury.to_account_info().try_borrow_mut_lamports()? += fees.platform;
+
+        // 2. Transfer 50 BPS to Material Dart
+        **staking_pool.try_borrow_mut_lamports()? -= fees.material_dart;
+        **self
+            .material_dart_wallet
+            .to_account_info()
+            .try_borrow_mut_lamports()? += fees.material_dart;
+
+        // 3. Transfer 100 BPS to bonus pool
+        // Note: Bonus Pool is also a PDA owned by this program.
+        // We can just move lamports directly.
+        **staking_pool.try_borrow_mut_lamports()? -= fees.bonus_pool;
+        **self
+            .bonus_pool
+            .to_account_info()
+            .try_borrow_mut_lamports()? += fees.bonus_pool;
+
+        // 4. Referral (50 BPS): the position's referrer directly, otherwise the referral pool
+        let referral_destination = match (self.user_stake.referrer, self.referrer.as_ref()) {
+            (Some(_), Some(referrer)) => referrer.to_account_info(),
+            (Some(_), None) => return err!(StakingError::InvalidReferrer),
+            (None, _) => self.referral_pool.to_account_info(),
+        };
+        **staking_pool.try_borrow_mut_lamports()? -= fees.referral;
+        **referral_destination.try_borrow_mut_lamports()? += fees.referral;
+
+        // 5. Pending rewards (NO FEE) to the user or their reward recipient
+        if rewards > 0 {
+            let reward_recipient = reward_recipient_account(
+                &self.user_stake,
+                &self.user,
+                &self.reward_recipient,
+                rewards,
+            )?;
+            **staking_pool.try_borrow_mut_lamports()? -= rewards;
+            **reward_recipient.try_borrow_mut_lamports()? += rewards;
+        }
+
+        // 6. Net unstake amount (90% by default)
+        **staking_pool.try_borrow_mut_lamports()? -= principal;
+        **self.user.to_account_info().try_borrow_mut_lamports()? += principal;
+
+        Ok(())
+    }
+
+    /// Pay out a token unstake from the vault; the bonus and referral pool
+    /// shares stay in the vault
+    fn transfer_unstake_tokens(
+        &self,
+        fees: &FeeBreakdown,
+        rewards: u64,
+        principal: u64,
+    ) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let mint = self.staking_pool.mint;
+
+        let user_token_account =
+            validate_token_account(self.user_token_account.as_ref(), &mint, &self.user.key())?;
+        let treasury_token_account = validate_token_account(
+            self.treasury_token_account.as_ref(),
+            &mint,
+            &self.treasury.key(),
+        )?;
+        let material_dart_token_account = validate_token_account(
+            self.material_dart_token_account.as_ref(),
+            &mint,
+            &self.material_dart_wallet.key(),
+        )?;
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]];
+        let pay = |to: AccountInfo<'info>, amount: u64| {
+            transfer_pool_tokens(
+                &tokens,
+                tokens.vault.to_account_info(),
+                to,
+                self.staking_pool.to_account_info(),
+                signer_seeds,
+                amount,
+            )
+        };
+
+        // 1. Platform commission and 2. Material Dart team
+        pay(treasury_token_account.to_account_info(), fees.platform)?;
+        pay(
+            material_dart_token_account.to_account_info(),
+            fees.material_dart,
+        )?;
+
+        // 4. The position's referrer is paid directly; the referral pool share
+        // stays in the vault
+        if self.user_stake.referrer.is_some() {
+            let referrer = self
+                .referrer
+                .as_ref()
+                .ok_or(StakingError::InvalidReferrer)?;
+            let referrer_token_account = validate_token_account(
+                self.referrer_token_account.as_ref(),
+                &mint,
+                &referrer.key(),
+            )?;
+            pay(referrer_token_account.to_account_info(), fees.referral)?;
+        }
+
+        // 5. Pending rewards (NO FEE) to the user or their reward recipient
+        if rewards > 0 {
+            let reward_token_account = reward_token_account(
+                &self.user_stake,
+                &mint,
+                self.user_token_account.as_ref(),
+                self.reward_recipient_token_account.as_ref(),
+            )?;
+            pay(reward_token_account.to_account_info(), rewards)?;
+        }
+
+        // 6. Net unstake amount
+        pay(user_token_account.to_account_info(), principal)
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnstaked<'info> {
+    #[account(
+        mut,
+        constraint = user.to_account_info().owner == &anchor_lang::solana_program::system_program::ID @ StakingError::InvalidAccountOwner
+    )]
+    pub user: Signer<'info>,
+
+    /// Global configuration
+    #[account(
+        seeds = [seeds::GLOBAL_CONFIG],
+        bump = global_config.bump,
+        owner = crate::ID,
+        constraint = !global_config.is_paused(pause_flags::UNSTAKE) @ StakingError::PoolPaused
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Staking pool (holds queued withdrawals)
+    #[account(
+        mut,
+        seeds = [seeds::STAKING_POOL, staking_pool.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    /// User's stake state
+    #[account(
+        mut,
+        seeds = [seeds::USER_STAKE, user.key().as_ref(), staking_pool.key().as_ref()],
+        bump = user_stake.bump,
+        owner = crate::ID,
+        constraint = user_stake.user == user.key() @ StakingError::Unauthorized
+    )]
+    pub user_stake: Account<'info, UserStakeState>,
+
+    /// Token pools: pool mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token pools: user's token account (receives the withdrawal)
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: pool vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token pools: Token or Token-2022 program
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn withdraw_unstaked_handler(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+    let user_key = ctx.accounts.user.key();
+    let amount = ctx.accounts.user_stake.pending_unstake;
+    let current_timestamp = get_current_timestamp()?;
+
+    require!(amount > 0, StakingError::NoPendingUnstake);
+    require!(
+        current_timestamp >= ctx.accounts.user_stake.unstake_ready_timestamp,
+        StakingError::CooldownNotElapsed
+    );
+
+    if ctx.accounts.staking_pool.is_token_pool() {
+        ctx.accounts.transfer_withdrawal_tokens(amount)?;
+    } else {
+        // Queued amounts may still be delegated until the authority withdraws them
+        let staking_pool = ctx.accounts.staking_pool.to_account_info();
+        require!(
+            pool_liquidity(&staking_pool)? >= amount,
+            StakingError::InsufficientLiquidity
+        );
+        **staking_pool.try_borrow_mut_lamports()? -= amount;
+        **ctx
+            .accounts
+            .user
+            .to_account_info()
+            .try_borrow_mut_lamports()? += amount;
+    }
+
+    let staking_pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    user_stake.pending_unstake = 0;
+    user_stake.unstake_ready_timestamp = 0;
+    staking_pool.total_pending_unstake = safe_sub(staking_pool.total_pending_unstake, amount)?;
+
+    emit!(UnstakeWithdrawn {
+        user: user_key,
+        pool: staking_pool.key(),
+        amount,
+        timestamp: current_timestamp,
+    });
+
+    msg!("✅ Unstake withdrawn!");
+    msg!("User: {}", user_key);
+    msg!("Amount: {} lamports", amount);
+
+    Ok(())
+}
+
+impl<'info> WithdrawUnstaked<'info> {
+    /// Pay a queued token withdrawal from the vault
+    fn transfer_withdrawal_tokens(&self, amount: u64) -> Result<()> {
+        let tokens = pool_token_accounts(
+            &self.staking_pool,
+            &self.mint,
+            &self.vault,
+            &self.token_program,
+        )?;
+        let user_token_account = validate_token_account(
+            self.user_token_account.as_ref(),
+            &self.staking_pool.mint,
+            &self.user.key(),
+        )?;
+
+        let pool_id = self.staking_pool.pool_id.to_le_bytes();
+        let bump = [self.staking_pool.bump];
+        transfer_pool_tokens(
+            &tokens,
+            tokens.vault.to_account_info(),
+            user_token_account.to_account_info(),
+            self.staking_pool.to_account_info(),
+            &[&[seeds::STAKING_POOL, pool_id.as_ref(), &bump]],
+            amount,
+        )
+    }
+}
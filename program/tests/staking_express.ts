@@ -20,6 +20,20 @@ describe("staking-express", () => {
     let treasury: anchor.web3.Keypair;
     let materialDartWallet: anchor.web3.Keypair;
 
+    // Token pool and liquid pool accounts (unused by the SOL pool)
+    const solPoolTokenAccounts = {
+        mint: null,
+        userTokenAccount: null,
+        vault: null,
+        treasuryTokenAccount: null,
+        materialDartTokenAccount: null,
+        referrerTokenAccount: null,
+        tokenProgram: null,
+        receiptMint: null,
+        userReceiptAccount: null,
+        receiptTokenProgram: null,
+    };
+
     // Constants
 
     before(async () => {
@@ -33,15 +47,15 @@ describe("staking-express", () => {
             program.programId
         );
         [stakingPool] = anchor.web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("staking_pool")],
+            [Buffer.from("staking_pool"), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
             program.programId
         );
         [bonusPool] = anchor.web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("bonus_pool")],
+            [Buffer.from("bonus_pool"), stakingPool.toBuffer()],
             program.programId
         );
         [referralPool] = anchor.web3.PublicKey.findProgramAddressSync(
-            [Buffer.from("referral_pool")],
+            [Buffer.from("referral_pool"), stakingPool.toBuffer()],
             program.programId
         );
     });
@@ -97,12 +111,16 @@ describe("staking-express", () => {
 
         // Stake 1 SOL
         await program.methods
-            .stake(stakeAmount)
+            .stake(stakeAmount, { none: {} })
             .accounts({
                 user: user.publicKey,
                 treasury: treasury.publicKey,
                 materialDartWallet: materialDartWallet.publicKey,
                 referrer: null,
+                referrerAccount: null,
+                allowlistEntry: null,
+                beneficiary: null,
+            ...solPoolTokenAccounts,
             })
             .signers([user])
             .rpc();
@@ -150,11 +168,15 @@ describe("staking-express", () => {
         );
 
         // 1. Stake 1 SOL
-        await program.methods.stake(new anchor.BN(1_000_000_000)).accounts({
+        await program.methods.stake(new anchor.BN(1_000_000_000), { none: {} }).accounts({
             user: user.publicKey,
             treasury: treasury.publicKey,
             materialDartWallet: materialDartWallet.publicKey,
             referrer: null,
+            referrerAccount: null,
+            allowlistEntry: null,
+            beneficiary: null,
+            ...solPoolTokenAccounts,
         }).signers([user]).rpc();
 
         // 2. Unstake 0.9 SOL (the entire staked balance)
@@ -165,6 +187,9 @@ describe("staking-express", () => {
             treasury: treasury.publicKey,
             materialDartWallet: materialDartWallet.publicKey,
             referrer: null,
+            rewardRecipient: null,
+            rewardRecipientTokenAccount: null,
+            ...solPoolTokenAccounts,
         }).signers([user]).rpc();
 
         // Verify user stake is 0
@@ -214,11 +239,15 @@ describe("staking-express", () => {
         const stakeAmount = new anchor.BN(10_000_000_000); // 10 SOL
         const netStakeA = 9_000_000_000; // 90%
 
-        await program.methods.stake(stakeAmount).accounts({
+        await program.methods.stake(stakeAmount, { none: {} }).accounts({
             user: userA.publicKey,
             treasury: treasury.publicKey,
             materialDartWallet: materialDartWallet.publicKey,
             referrer: null,
+            referrerAccount: null,
+            allowlistEntry: null,
+            beneficiary: null,
+            ...solPoolTokenAccounts,
         }).signers([userA]).rpc();
 
         // 2. User B stakes 10 SOL
@@ -237,11 +266,15 @@ describe("staking-express", () => {
         console.log(`Expected Ratio: ${netStakeA}/${rewardPoolSize} = ${netStakeA / rewardPoolSize}`);
         console.log("Expected Reward for A:", expectedRewardA);
 
-        await program.methods.stake(stakeAmount).accounts({
+        await program.methods.stake(stakeAmount, { none: {} }).accounts({
             user: userB.publicKey,
             treasury: treasury.publicKey,
             materialDartWallet: materialDartWallet.publicKey,
             referrer: null,
+            referrerAccount: null,
+            allowlistEntry: null,
+            beneficiary: null,
+            ...solPoolTokenAccounts,
         }).signers([userB]).rpc();
 
         // 3. Claim
@@ -249,6 +282,12 @@ describe("staking-express", () => {
 
         await program.methods.claimRewards().accounts({
             user: userA.publicKey,
+            mint: null,
+            userTokenAccount: null,
+            vault: null,
+            tokenProgram: null,
+            rewardRecipient: null,
+            rewardRecipientTokenAccount: null,
         }).signers([userA]).rpc();
 
         const balanceAfter = await provider.connection.getBalance(userA.publicKey);
@@ -292,11 +331,15 @@ describe("staking-express", () => {
         const stakeAmount = new anchor.BN(1_000_000_000); // 1 SOL
         const referrerBalanceBefore = await provider.connection.getBalance(referrer.publicKey);
 
-        await program.methods.stake(stakeAmount).accounts({
+        await program.methods.stake(stakeAmount, { none: {} }).accounts({
             user: user.publicKey,
             treasury: treasury.publicKey,
             materialDartWallet: materialDartWallet.publicKey,
             referrer: referrer.publicKey, // Passed here
+            referrerAccount: null,
+            allowlistEntry: null,
+            beneficiary: null,
+            ...solPoolTokenAccounts,
         }).signers([user]).rpc();
 
         // Verify Referrer got 50 BPS (0.5%) = 0.005 SOL = 5,000,000 lamports
@@ -324,11 +367,15 @@ describe("staking-express", () => {
             program.programId
         );
 
-        await program.methods.stake(new anchor.BN(1_000_000_000)).accounts({
+        await program.methods.stake(new anchor.BN(1_000_000_000), { none: {} }).accounts({
             user: user.publicKey,
             treasury: treasury.publicKey,
             materialDartWallet: materialDartWallet.publicKey,
             referrer: null,
+            referrerAccount: null,
+            allowlistEntry: null,
+            beneficiary: null,
+            ...solPoolTokenAccounts,
         }).signers([user]).rpc();
 
         bonusAccount = await program.account.bonusPool.fetch(bonusPool);
@@ -371,6 +418,17 @@ describe("staking-express", () => {
 
         // Split should be exactly half (or off by 1 due to rounding)
         expect(referralAccount.balance.toNumber()).to.be.closeTo(initialBalance / 2, 1);
+
+        // Closed period is recorded as epoch 0
+        const [referralEpoch] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("referral_epoch"), referralPool.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const epochAccount = await program.account.referralEpoch.fetch(referralEpoch);
+        expect(epochAccount.epoch.toNumber()).to.equal(0);
+        expect(epochAccount.balanceIn.toNumber()).to.equal(initialBalance);
+        expect(epochAccount.carriedForward.toNumber()).to.equal(referralAccount.balance.toNumber());
+        expect(referralAccount.currentEpoch.toNumber()).to.equal(1);
     });
 
     it("Distributes Bonus Pool (Expect Error)", async () => {